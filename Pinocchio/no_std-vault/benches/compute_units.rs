@@ -0,0 +1,96 @@
+//! Compute units consumed by deposit and withdraw.
+//!
+//! Build the program before running:
+//!
+//! ```text
+//! cargo build-sbf
+//! cargo bench --bench compute_units
+//! ```
+
+use litesvm::LiteSVM;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::Transaction,
+};
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+const PROGRAM_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/deploy/blueshift_vault.so");
+
+struct Measurement {
+    deposit: u64,
+    withdraw: u64,
+}
+
+fn program_id() -> Pubkey {
+    Pubkey::new_from_array(blueshift_vault::ID.to_bytes())
+}
+
+fn vault_accounts(owner: &Pubkey) -> Vec<AccountMeta> {
+    let (vault, _) = Pubkey::find_program_address(&[b"vault", owner.as_ref()], &program_id());
+    let (state, _) = Pubkey::find_program_address(&[b"state", owner.as_ref()], &program_id());
+
+    vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new(state, false),
+    ]
+}
+
+fn send(svm: &mut LiteSVM, owner: &Keypair, accounts: Vec<AccountMeta>, data: Vec<u8>) -> u64 {
+    let ix = Instruction { program_id: program_id(), accounts, data };
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&owner.pubkey()), &[owner], svm.latest_blockhash());
+
+    svm.send_transaction(tx)
+        .unwrap_or_else(|err| panic!("transaction failed: {:?}", err.err))
+        .compute_units_consumed
+}
+
+fn measure(program: &[u8]) -> Measurement {
+    let mut svm = LiteSVM::new();
+    svm.add_program(program_id(), program);
+
+    let owner = Keypair::new();
+    svm.airdrop(&owner.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+    let (state, _) = Pubkey::find_program_address(&[b"state", owner.pubkey().as_ref()], &program_id());
+    let mut initialize = vec![3];
+    initialize.extend_from_slice(&0i64.to_le_bytes());
+    send(
+        &mut svm,
+        &owner,
+        vec![
+            AccountMeta::new(owner.pubkey(), true),
+            AccountMeta::new(state, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        initialize,
+    );
+
+    let mut deposit = vec![0];
+    deposit.extend_from_slice(&LAMPORTS_PER_SOL.to_le_bytes());
+    // No config yet, so the deposit takes no fee and the owner stands in as treasury
+    let (config, _) = Pubkey::find_program_address(&[b"config"], &program_id());
+    let mut deposit_accounts = vault_accounts(&owner.pubkey());
+    deposit_accounts.push(AccountMeta::new_readonly(config, false));
+    deposit_accounts.push(AccountMeta::new(owner.pubkey(), false));
+    let deposit = send(&mut svm, &owner, deposit_accounts, deposit);
+    let withdraw = send(&mut svm, &owner, vault_accounts(&owner.pubkey()), vec![1]);
+
+    Measurement { deposit, withdraw }
+}
+
+fn main() {
+    let program = std::fs::read(PROGRAM_PATH)
+        .unwrap_or_else(|_| panic!("{PROGRAM_PATH} is missing, run `cargo build-sbf` first"));
+    let measurement = measure(&program);
+
+    println!("| Deposit (CU) | Withdraw (CU) |");
+    println!("|--------------|---------------|");
+    println!("| {} | {} |", measurement.deposit, measurement.withdraw);
+}
@@ -0,0 +1,650 @@
+//! Host-side helpers for building vault instructions and reading vault state.
+//!
+//! Only compiled with the `client` feature; the on-chain program stays `no_std`.
+
+extern crate std;
+
+use std::string::ToString;
+use std::vec;
+use std::vec::Vec;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use pinocchio::{address::Address, sysvars::instructions::INSTRUCTIONS_ID};
+use solana_instruction::{AccountMeta, Instruction};
+
+use crate::{
+    find_config_address, find_stake_address, find_state_address, find_stream_address,
+    find_stream_escrow_address, find_vault_address, AddAllowlistEntry, CancelStream, Claim,
+    ClaimStream, Close, Config, ConfigureMultisig, CreateStream, DeactivateStake, DelegateStake,
+    Deposit, DepositFor, DepositToken, ExtendLock, Initialize, InitializeConfig, PartialWithdraw,
+    RemoveAllowlistEntry, SetBeneficiary, SetHook, SetWithdrawCap, Stream, UpdateConfig,
+    VaultEvent, VaultState, Withdraw, WithdrawSigned, WithdrawSignedData, WithdrawStake,
+    WithdrawTo, WithdrawToken, BPF_LOADER_UPGRADEABLE_ID, CLOCK_SYSVAR_ID, ID, RENT_SYSVAR_ID,
+    STAKE_CONFIG_ID, STAKE_HISTORY_SYSVAR_ID, STAKE_PROGRAM_ID, VERSIONED,
+};
+
+/// Extra multisig signers are appended after an instruction's fixed accounts
+fn with_signers(mut accounts: Vec<AccountMeta>, signers: &[Address]) -> Vec<AccountMeta> {
+    accounts.extend(signers.iter().map(|signer| AccountMeta::new_readonly(*signer, true)));
+    accounts
+}
+
+fn vault_accounts(owner: &Address) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(find_vault_address(owner).0, false),
+        AccountMeta::new_readonly(pinocchio_system::ID, false),
+        AccountMeta::new(find_state_address(owner).0, false),
+    ]
+}
+
+fn token_accounts(
+    owner: &Address,
+    mint: &Address,
+    owner_token_account: &Address,
+    vault_token_account: &Address,
+    token_program: &Address,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new_readonly(find_vault_address(owner).0, false),
+        AccountMeta::new(find_state_address(owner).0, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(*owner_token_account, false),
+        AccountMeta::new(*vault_token_account, false),
+        AccountMeta::new_readonly(*token_program, false),
+    ]
+}
+
+/// `[owner, recipient, stream, escrow, system_program]` with either the
+/// owner or the recipient signing
+fn stream_accounts(owner: &Address, recipient: &Address, owner_signs: bool) -> Vec<AccountMeta> {
+    let stream = find_stream_address(owner, recipient).0;
+
+    vec![
+        AccountMeta::new(*owner, owner_signs),
+        AccountMeta::new(*recipient, !owner_signs),
+        AccountMeta::new(stream, false),
+        AccountMeta::new(find_stream_escrow_address(&stream).0, false),
+        AccountMeta::new_readonly(pinocchio_system::ID, false),
+    ]
+}
+
+/// `[owner, vault, state, stake, stake_program, clock]`
+fn stake_accounts(owner: &Address) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(find_vault_address(owner).0, false),
+        AccountMeta::new(find_state_address(owner).0, false),
+        AccountMeta::new(find_stake_address(owner).0, false),
+        AccountMeta::new_readonly(STAKE_PROGRAM_ID, false),
+        AccountMeta::new_readonly(CLOCK_SYSVAR_ID, false),
+    ]
+}
+
+/// `treasury` must match the config once it is initialized; before that any
+/// account will do
+fn deposit_accounts(owner: &Address, treasury: &Address) -> Vec<AccountMeta> {
+    let mut accounts = vault_accounts(owner);
+    accounts.push(AccountMeta::new_readonly(find_config_address().0, false));
+    accounts.push(AccountMeta::new(*treasury, false));
+    accounts
+}
+
+fn data_with(discriminator: u8, payload: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(1 + payload.len());
+    data.push(discriminator);
+    data.extend_from_slice(payload);
+    data
+}
+
+/// Explicit `[discriminator | VERSIONED, version, payload..]` envelope
+fn versioned_data_with(discriminator: u8, version: u8, payload: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(2 + payload.len());
+    data.push(discriminator | VERSIONED);
+    data.push(version);
+    data.extend_from_slice(payload);
+    data
+}
+
+pub fn initialize(owner: &Address, unlock_timestamp: i64) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(find_state_address(owner).0, false),
+            AccountMeta::new_readonly(pinocchio_system::ID, false),
+        ],
+        data: data_with(*Initialize::DISCRIMINATOR, &unlock_timestamp.to_le_bytes()),
+    }
+}
+
+/// Takes the config PDA and `treasury` after the state account, which
+/// clients from before the protocol fee did not pass. The fee is whatever
+/// the config charges when the deposit lands; a raise only applies after
+/// [`FEE_CHANGE_DELAY`](crate::FEE_CHANGE_DELAY), and
+/// [`deposit_with_max_fee`] bounds it exactly
+pub fn deposit(owner: &Address, treasury: &Address, amount: u64) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: deposit_accounts(owner, treasury),
+        data: data_with(*Deposit::DISCRIMINATOR, &amount.to_le_bytes()),
+    }
+}
+
+/// Version 1 deposit that fails if the protocol fee would exceed `max_fee`,
+/// e.g. because the admin raised it after the transaction was signed
+pub fn deposit_with_max_fee(
+    owner: &Address,
+    treasury: &Address,
+    amount: u64,
+    max_fee: u64,
+) -> Instruction {
+    let mut payload = Vec::with_capacity(16);
+    payload.extend_from_slice(&amount.to_le_bytes());
+    payload.extend_from_slice(&max_fee.to_le_bytes());
+
+    Instruction {
+        program_id: ID,
+        accounts: deposit_accounts(owner, treasury),
+        data: versioned_data_with(*Deposit::DISCRIMINATOR, 1, &payload),
+    }
+}
+
+/// Funds `owner`'s vault from `payer`; the owner does not sign
+pub fn deposit_for(
+    payer: &Address,
+    owner: &Address,
+    treasury: &Address,
+    amount: u64,
+) -> Instruction {
+    let mut accounts = deposit_accounts(owner, treasury);
+    accounts[0] = AccountMeta::new_readonly(*owner, false);
+    accounts.insert(0, AccountMeta::new(*payer, true));
+
+    Instruction {
+        program_id: ID,
+        accounts,
+        data: data_with(*DepositFor::DISCRIMINATOR, &amount.to_le_bytes()),
+    }
+}
+
+/// Version 1 [`deposit_for`] that fails if the protocol fee would exceed
+/// `max_fee`
+pub fn deposit_for_with_max_fee(
+    payer: &Address,
+    owner: &Address,
+    treasury: &Address,
+    amount: u64,
+    max_fee: u64,
+) -> Instruction {
+    let mut payload = Vec::with_capacity(16);
+    payload.extend_from_slice(&amount.to_le_bytes());
+    payload.extend_from_slice(&max_fee.to_le_bytes());
+
+    Instruction {
+        data: versioned_data_with(*DepositFor::DISCRIMINATOR, 1, &payload),
+        ..deposit_for(payer, owner, treasury, amount)
+    }
+}
+
+/// Appends the vault's registered hook program to a `deposit` or
+/// `deposit_for` instruction
+pub fn with_hook(mut instruction: Instruction, hook: &Address) -> Instruction {
+    instruction.accounts.push(AccountMeta::new_readonly(*hook, false));
+    instruction
+}
+
+/// `signers` are the additional multisig signers, empty for a regular vault
+pub fn withdraw(owner: &Address, signers: &[Address]) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(vault_accounts(owner), signers),
+        data: vec![*Withdraw::DISCRIMINATOR],
+    }
+}
+
+/// `signers` are the additional multisig signers, empty for a regular vault
+pub fn partial_withdraw(owner: &Address, amount: u64, signers: &[Address]) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(vault_accounts(owner), signers),
+        data: data_with(*PartialWithdraw::DISCRIMINATOR, &amount.to_le_bytes()),
+    }
+}
+
+/// Pays `amount` from the owner's vault straight to `recipient`
+pub fn withdraw_to(
+    owner: &Address,
+    recipient: &Address,
+    amount: u64,
+    signers: &[Address],
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new(*recipient, false)];
+    accounts.extend(vault_accounts(owner));
+
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(accounts, signers),
+        data: data_with(*WithdrawTo::DISCRIMINATOR, &amount.to_le_bytes()),
+    }
+}
+
+/// Message the owner signs off-chain to authorize [`withdraw_signed`];
+/// `init_slot` is the vault's current [`VaultState::init_slot`]
+pub fn withdraw_authorization(
+    owner: &Address,
+    recipient: &Address,
+    init_slot: u64,
+    amount: u64,
+    nonce: u64,
+    expiry: i64,
+) -> [u8; WithdrawSignedData::MESSAGE_LEN] {
+    WithdrawSignedData { amount, nonce, expiry }
+        .message(&find_vault_address(owner).0, recipient, init_slot)
+}
+
+/// Relayed withdrawal; it must directly follow an Ed25519 program
+/// instruction verifying the owner's signature over
+/// [`withdraw_authorization`]. `nonce` is the vault's current
+/// [`VaultState::nonce`].
+pub fn withdraw_signed(
+    owner: &Address,
+    recipient: &Address,
+    amount: u64,
+    nonce: u64,
+    expiry: i64,
+    signers: &[Address],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*recipient, false),
+        AccountMeta::new_readonly(INSTRUCTIONS_ID, false),
+    ];
+    accounts.extend(vault_accounts(owner));
+    // The owner authorizes off-chain instead of signing the transaction
+    accounts[2].is_signer = false;
+
+    let mut payload = Vec::with_capacity(WithdrawSignedData::LEN);
+    payload.extend_from_slice(&amount.to_le_bytes());
+    payload.extend_from_slice(&nonce.to_le_bytes());
+    payload.extend_from_slice(&expiry.to_le_bytes());
+
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(accounts, signers),
+        data: data_with(*WithdrawSigned::DISCRIMINATOR, &payload),
+    }
+}
+
+/// Sweeps the vault to the owner and closes the state account
+pub fn close(owner: &Address, signers: &[Address]) -> Instruction {
+    let mut accounts = vault_accounts(owner);
+    accounts.push(AccountMeta::new_readonly(find_stake_address(owner).0, false));
+
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(accounts, signers),
+        data: vec![*Close::DISCRIMINATOR],
+    }
+}
+
+/// `signers` are the additional multisig signers, empty for a regular vault
+pub fn extend_lock(owner: &Address, unlock_timestamp: i64, signers: &[Address]) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(
+            vec![
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new(find_state_address(owner).0, false),
+            ],
+            signers,
+        ),
+        data: data_with(*ExtendLock::DISCRIMINATOR, &unlock_timestamp.to_le_bytes()),
+    }
+}
+
+pub fn deposit_token(
+    owner: &Address,
+    mint: &Address,
+    owner_token_account: &Address,
+    vault_token_account: &Address,
+    token_program: &Address,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: token_accounts(owner, mint, owner_token_account, vault_token_account, token_program),
+        data: data_with(*DepositToken::DISCRIMINATOR, &amount.to_le_bytes()),
+    }
+}
+
+/// `signers` are the additional multisig signers, empty for a regular vault
+pub fn withdraw_token(
+    owner: &Address,
+    mint: &Address,
+    owner_token_account: &Address,
+    vault_token_account: &Address,
+    token_program: &Address,
+    amount: u64,
+    signers: &[Address],
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(
+            token_accounts(owner, mint, owner_token_account, vault_token_account, token_program),
+            signers,
+        ),
+        data: data_with(*WithdrawToken::DISCRIMINATOR, &amount.to_le_bytes()),
+    }
+}
+
+/// `new_signers` replaces the signer set; `current_signers` must meet the
+/// existing threshold when the vault is already in multisig mode
+pub fn configure_multisig(
+    owner: &Address,
+    threshold: u8,
+    new_signers: &[Address],
+    current_signers: &[Address],
+) -> Instruction {
+    let mut payload = vec![threshold];
+    for signer in new_signers {
+        payload.extend_from_slice(signer.as_ref());
+    }
+
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(
+            vec![
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new(find_state_address(owner).0, false),
+            ],
+            current_signers,
+        ),
+        data: data_with(*ConfigureMultisig::DISCRIMINATOR, &payload),
+    }
+}
+
+/// `beneficiary: None` removes the current beneficiary
+pub fn set_beneficiary(
+    owner: &Address,
+    beneficiary: Option<&Address>,
+    inactivity_period: i64,
+    signers: &[Address],
+) -> Instruction {
+    let mut payload = beneficiary.map_or([0; 32], |beneficiary| beneficiary.to_bytes()).to_vec();
+    payload.extend_from_slice(&inactivity_period.to_le_bytes());
+
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(
+            vec![
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new(find_state_address(owner).0, false),
+            ],
+            signers,
+        ),
+        data: data_with(*SetBeneficiary::DISCRIMINATOR, &payload),
+    }
+}
+
+pub fn claim(beneficiary: &Address, owner: &Address) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*beneficiary, true),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new(find_vault_address(owner).0, false),
+            AccountMeta::new_readonly(pinocchio_system::ID, false),
+            AccountMeta::new(find_state_address(owner).0, false),
+        ],
+        data: vec![*Claim::DISCRIMINATOR],
+    }
+}
+
+/// `hook: None` removes the current deposit hook
+pub fn set_hook(owner: &Address, hook: Option<&Address>, signers: &[Address]) -> Instruction {
+    let hook = hook.map_or([0; 32], |hook| hook.to_bytes());
+
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(
+            vec![
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new(find_state_address(owner).0, false),
+            ],
+            signers,
+        ),
+        data: data_with(*SetHook::DISCRIMINATOR, &hook),
+    }
+}
+
+/// `destination` can be paid once `activation_delay` seconds have passed
+pub fn add_allowlist_entry(
+    owner: &Address,
+    destination: &Address,
+    activation_delay: i64,
+    signers: &[Address],
+) -> Instruction {
+    let mut payload = destination.to_bytes().to_vec();
+    payload.extend_from_slice(&activation_delay.to_le_bytes());
+
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(
+            vec![
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new(find_state_address(owner).0, false),
+            ],
+            signers,
+        ),
+        data: data_with(*AddAllowlistEntry::DISCRIMINATOR, &payload),
+    }
+}
+
+pub fn remove_allowlist_entry(
+    owner: &Address,
+    destination: &Address,
+    signers: &[Address],
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(
+            vec![
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new(find_state_address(owner).0, false),
+            ],
+            signers,
+        ),
+        data: data_with(*RemoveAllowlistEntry::DISCRIMINATOR, destination.as_ref()),
+    }
+}
+
+/// `cap` is in lamports per epoch, zero for unlimited; raising it is delayed
+/// by [`WITHDRAW_CAP_DELAY`](crate::WITHDRAW_CAP_DELAY)
+pub fn set_withdraw_cap(owner: &Address, cap: u64, signers: &[Address]) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(
+            vec![
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new(find_state_address(owner).0, false),
+            ],
+            signers,
+        ),
+        data: data_with(*SetWithdrawCap::DISCRIMINATOR, &cap.to_le_bytes()),
+    }
+}
+
+pub fn create_stream(
+    owner: &Address,
+    recipient: &Address,
+    amount: u64,
+    start_time: i64,
+    end_time: i64,
+) -> Instruction {
+    let mut payload = Vec::with_capacity(24);
+    payload.extend_from_slice(&amount.to_le_bytes());
+    payload.extend_from_slice(&start_time.to_le_bytes());
+    payload.extend_from_slice(&end_time.to_le_bytes());
+
+    Instruction {
+        program_id: ID,
+        accounts: stream_accounts(owner, recipient, true),
+        data: data_with(*CreateStream::DISCRIMINATOR, &payload),
+    }
+}
+
+/// Signed by the recipient
+pub fn claim_stream(owner: &Address, recipient: &Address) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: stream_accounts(owner, recipient, false),
+        data: vec![*ClaimStream::DISCRIMINATOR],
+    }
+}
+
+pub fn cancel_stream(owner: &Address, recipient: &Address) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: stream_accounts(owner, recipient, true),
+        data: vec![*CancelStream::DISCRIMINATOR],
+    }
+}
+
+/// Stakes `amount` vault lamports with `vote`; the amount must cover the
+/// stake account rent plus the minimum delegation
+pub fn delegate_stake(
+    owner: &Address,
+    vote: &Address,
+    amount: u64,
+    signers: &[Address],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*vote, false),
+        AccountMeta::new_readonly(STAKE_HISTORY_SYSVAR_ID, false),
+        AccountMeta::new_readonly(STAKE_CONFIG_ID, false),
+        AccountMeta::new_readonly(RENT_SYSVAR_ID, false),
+        AccountMeta::new_readonly(pinocchio_system::ID, false),
+    ];
+    accounts.extend(stake_accounts(owner));
+
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(accounts, signers),
+        data: data_with(*DelegateStake::DISCRIMINATOR, &amount.to_le_bytes()),
+    }
+}
+
+pub fn deactivate_stake(owner: &Address, signers: &[Address]) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(stake_accounts(owner), signers),
+        data: vec![*DeactivateStake::DISCRIMINATOR],
+    }
+}
+
+/// Returns the deactivated stake and its rewards to the vault
+pub fn withdraw_stake(owner: &Address, signers: &[Address]) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(STAKE_HISTORY_SYSVAR_ID, false)];
+    accounts.extend(stake_accounts(owner));
+
+    Instruction {
+        program_id: ID,
+        accounts: with_signers(accounts, signers),
+        data: vec![*WithdrawStake::DISCRIMINATOR],
+    }
+}
+
+/// `admin` must be the program's upgrade authority
+pub fn initialize_config(admin: &Address, treasury: &Address, fee_bps: u16) -> Instruction {
+    let program_data = Address::find_program_address(&[ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0;
+
+    let mut payload = Vec::with_capacity(34);
+    payload.extend_from_slice(treasury.as_ref());
+    payload.extend_from_slice(&fee_bps.to_le_bytes());
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(find_config_address().0, false),
+            AccountMeta::new_readonly(program_data, false),
+            AccountMeta::new_readonly(pinocchio_system::ID, false),
+        ],
+        data: data_with(*InitializeConfig::DISCRIMINATOR, &payload),
+    }
+}
+
+/// Signed by the current admin; pass it again as `new_admin` to keep it. A
+/// higher `fee_bps` only applies after [`FEE_CHANGE_DELAY`](crate::FEE_CHANGE_DELAY)
+pub fn update_config(
+    admin: &Address,
+    new_admin: &Address,
+    treasury: &Address,
+    fee_bps: u16,
+) -> Instruction {
+    let mut payload = Vec::with_capacity(66);
+    payload.extend_from_slice(new_admin.as_ref());
+    payload.extend_from_slice(treasury.as_ref());
+    payload.extend_from_slice(&fee_bps.to_le_bytes());
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(find_config_address().0, false),
+        ],
+        data: data_with(*UpdateConfig::DISCRIMINATOR, &payload),
+    }
+}
+
+/// Reads the config account's data as fetched from RPC
+pub fn decode_config(data: &[u8]) -> Option<&Config> {
+    Config::from_bytes(data).ok()
+}
+
+/// Reads a stream account's data as fetched from RPC
+pub fn decode_stream(data: &[u8]) -> Option<&Stream> {
+    Stream::from_bytes(data).ok()
+}
+
+/// Reads a state account's data as fetched from RPC
+pub fn decode_state(data: &[u8]) -> Option<&VaultState> {
+    VaultState::from_bytes(data).ok()
+}
+
+/// Extracts the vault events from a transaction's log messages.
+///
+/// Only `Program data:` lines logged while this program is the innermost
+/// invocation are decoded, so data logged by hooks or other programs in the
+/// same transaction is ignored.
+pub fn parse_events<S: AsRef<str>>(logs: &[S]) -> Vec<VaultEvent> {
+    let program_id = ID.to_string();
+    let mut invocations: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for log in logs {
+        let log = log.as_ref();
+
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            if invocations.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            // Events are logged as a single field
+            if let Some(event) = STANDARD.decode(data).ok().and_then(|bytes| VaultEvent::from_bytes(&bytes)) {
+                events.push(event);
+            }
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut parts = rest.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some(id), Some("invoke")) => invocations.push(id),
+                (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                    invocations.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    events
+}
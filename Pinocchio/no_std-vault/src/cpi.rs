@@ -0,0 +1,282 @@
+//! Typed CPI builders for Pinocchio programs calling into the vault, in the
+//! style of `pinocchio_system::instructions`.
+//!
+//! Only compiled with the `cpi` feature, which also leaves out this crate's
+//! entrypoint and panic handler.
+
+use pinocchio::{
+    account::AccountView,
+    address::Address,
+    cpi::{invoke_signed, slice_invoke_signed, Signer},
+    instruction::{InstructionAccount, InstructionView},
+    ProgramResult,
+};
+
+/// Deposit lamports into the owner's vault.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` Owner
+///   1. `[WRITE]` Vault PDA
+///   2. `[]` System program
+///   3. `[WRITE]` State PDA
+///   4. `[]` Config PDA
+///   5. `[WRITE]` Treasury from the config
+///   6. `[]` Deposit hook program, if the vault has one
+pub struct Deposit<'a> {
+    /// Vault owner funding the deposit.
+    pub owner: &'a AccountView,
+
+    /// The owner's vault.
+    pub vault: &'a AccountView,
+
+    /// System program.
+    pub system_program: &'a AccountView,
+
+    /// The owner's vault state.
+    pub state: &'a AccountView,
+
+    /// Global fee config.
+    pub config: &'a AccountView,
+
+    /// Treasury receiving the protocol fee.
+    pub treasury: &'a AccountView,
+
+    /// Hook program registered on the vault, if any.
+    pub hook_program: Option<&'a AccountView>,
+
+    /// Amount of lamports to deposit.
+    pub lamports: u64,
+}
+
+impl Deposit<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let hook_program = self.hook_program.map(AccountView::address);
+        let (instruction_accounts, len) = deposit_accounts(
+            self.owner.address(),
+            self.vault.address(),
+            self.system_program.address(),
+            self.state.address(),
+            self.config.address(),
+            self.treasury.address(),
+            hook_program,
+        );
+        let data = amount_data(*crate::Deposit::DISCRIMINATOR, self.lamports);
+
+        let instruction = InstructionView {
+            program_id: &crate::ID,
+            accounts: &instruction_accounts[..len],
+            data: &data,
+        };
+
+        // Without a hook the last slot is never passed
+        let accounts = [
+            self.owner,
+            self.vault,
+            self.system_program,
+            self.state,
+            self.config,
+            self.treasury,
+            self.hook_program.unwrap_or(self.system_program),
+        ];
+
+        slice_invoke_signed(&instruction, &accounts[..len], signers)
+    }
+}
+
+/// Deposit lamports from a payer into someone else's vault.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` Payer
+///   1. `[]` Owner
+///   2. `[WRITE]` Vault PDA
+///   3. `[]` System program
+///   4. `[WRITE]` State PDA
+///   5. `[]` Config PDA
+///   6. `[WRITE]` Treasury from the config
+///   7. `[]` Deposit hook program, if the vault has one
+pub struct DepositFor<'a> {
+    /// Account funding the deposit.
+    pub payer: &'a AccountView,
+
+    /// Owner of the vault being funded.
+    pub owner: &'a AccountView,
+
+    /// The owner's vault.
+    pub vault: &'a AccountView,
+
+    /// System program.
+    pub system_program: &'a AccountView,
+
+    /// The owner's vault state.
+    pub state: &'a AccountView,
+
+    /// Global fee config.
+    pub config: &'a AccountView,
+
+    /// Treasury receiving the protocol fee.
+    pub treasury: &'a AccountView,
+
+    /// Hook program registered on the vault, if any.
+    pub hook_program: Option<&'a AccountView>,
+
+    /// Amount of lamports to deposit.
+    pub lamports: u64,
+}
+
+impl DepositFor<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let hook_program = self.hook_program.map(AccountView::address);
+        let (instruction_accounts, len) = deposit_for_accounts(
+            self.payer.address(),
+            self.owner.address(),
+            self.vault.address(),
+            self.system_program.address(),
+            self.state.address(),
+            self.config.address(),
+            self.treasury.address(),
+            hook_program,
+        );
+        let data = amount_data(*crate::DepositFor::DISCRIMINATOR, self.lamports);
+
+        let instruction = InstructionView {
+            program_id: &crate::ID,
+            accounts: &instruction_accounts[..len],
+            data: &data,
+        };
+
+        // Without a hook the last slot is never passed
+        let accounts = [
+            self.payer,
+            self.owner,
+            self.vault,
+            self.system_program,
+            self.state,
+            self.config,
+            self.treasury,
+            self.hook_program.unwrap_or(self.system_program),
+        ];
+
+        slice_invoke_signed(&instruction, &accounts[..len], signers)
+    }
+}
+
+/// `[discriminator, lamports (u64 LE)]`
+pub(crate) fn amount_data(discriminator: u8, lamports: u64) -> [u8; 9] {
+    let mut data = [0u8; 9];
+    data[0] = discriminator;
+    data[1..].copy_from_slice(&lamports.to_le_bytes());
+    data
+}
+
+/// [`Deposit`] account metas and how many of them are used; the hook
+/// program slot is only counted when there is one
+pub(crate) fn deposit_accounts<'b>(
+    owner: &'b Address,
+    vault: &'b Address,
+    system_program: &'b Address,
+    state: &'b Address,
+    config: &'b Address,
+    treasury: &'b Address,
+    hook_program: Option<&'b Address>,
+) -> ([InstructionAccount<'b>; 7], usize) {
+    let instruction_accounts = [
+        InstructionAccount::writable_signer(owner),
+        InstructionAccount::writable(vault),
+        InstructionAccount::readonly(system_program),
+        InstructionAccount::writable(state),
+        InstructionAccount::readonly(config),
+        InstructionAccount::writable(treasury),
+        InstructionAccount::readonly(hook_program.unwrap_or(system_program)),
+    ];
+
+    (instruction_accounts, 6 + hook_program.is_some() as usize)
+}
+
+/// [`DepositFor`] account metas and how many of them are used; the hook
+/// program slot is only counted when there is one
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn deposit_for_accounts<'b>(
+    payer: &'b Address,
+    owner: &'b Address,
+    vault: &'b Address,
+    system_program: &'b Address,
+    state: &'b Address,
+    config: &'b Address,
+    treasury: &'b Address,
+    hook_program: Option<&'b Address>,
+) -> ([InstructionAccount<'b>; 8], usize) {
+    let instruction_accounts = [
+        InstructionAccount::writable_signer(payer),
+        InstructionAccount::readonly(owner),
+        InstructionAccount::writable(vault),
+        InstructionAccount::readonly(system_program),
+        InstructionAccount::writable(state),
+        InstructionAccount::readonly(config),
+        InstructionAccount::writable(treasury),
+        InstructionAccount::readonly(hook_program.unwrap_or(system_program)),
+    ];
+
+    (instruction_accounts, 7 + hook_program.is_some() as usize)
+}
+
+/// Withdraw every lamport from the owner's vault back to the owner.
+///
+/// Multisig vaults need their co-signers as extra accounts, which this
+/// builder does not pass; it only covers single-owner vaults.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` Owner
+///   1. `[WRITE]` Vault PDA
+///   2. `[]` System program
+///   3. `[WRITE]` State PDA
+pub struct Withdraw<'a> {
+    /// Vault owner receiving the lamports.
+    pub owner: &'a AccountView,
+
+    /// The owner's vault.
+    pub vault: &'a AccountView,
+
+    /// System program.
+    pub system_program: &'a AccountView,
+
+    /// The owner's vault state.
+    pub state: &'a AccountView,
+}
+
+impl Withdraw<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let instruction_accounts = [
+            InstructionAccount::writable_signer(self.owner.address()),
+            InstructionAccount::writable(self.vault.address()),
+            InstructionAccount::readonly(self.system_program.address()),
+            InstructionAccount::writable(self.state.address()),
+        ];
+
+        let instruction = InstructionView {
+            program_id: &crate::ID,
+            accounts: &instruction_accounts,
+            data: &[*crate::Withdraw::DISCRIMINATOR],
+        };
+
+        invoke_signed(
+            &instruction,
+            &[self.owner, self.vault, self.system_program, self.state],
+            signers,
+        )
+    }
+}
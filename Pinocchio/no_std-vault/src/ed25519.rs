@@ -0,0 +1,75 @@
+use pinocchio::{
+    account::AccountView, address::Address, sysvars::instructions::Instructions, ProgramResult,
+};
+
+use crate::VaultError;
+
+/// Native program verifying Ed25519 signatures
+/// (`Ed25519SigVerify111111111111111111111111111`)
+pub const ED25519_PROGRAM_ID: Address = Address::new_from_array([
+    0x03, 0x7d, 0x46, 0xd6, 0x7c, 0x93, 0xfb, 0xbe, 0x12, 0xf9, 0x42, 0x8f, 0x83, 0x8d, 0x40, 0xff,
+    0x05, 0x70, 0x74, 0x49, 0x27, 0xf4, 0x8a, 0x64, 0xfc, 0xca, 0x70, 0x44, 0x80, 0x00, 0x00, 0x00,
+]);
+
+/// `[signature count (u8), padding (u8)]`
+const HEADER_LEN: usize = 2;
+
+/// Seven u16 offsets describing where one signature, key and message live
+const OFFSETS_LEN: usize = 14;
+
+/// Instruction index the Ed25519 program uses for "this instruction"
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Checks that the instruction right before the current one is an Ed25519
+/// program instruction verifying a single signature by `signer` over
+/// exactly `message`.
+///
+/// The Ed25519 program fails the whole transaction on a bad signature, so
+/// only the public key and message it verified need matching here.
+pub fn check_ed25519_signature(
+    instructions: &AccountView,
+    signer: &Address,
+    message: &[u8],
+) -> ProgramResult {
+    let instructions = Instructions::try_from(instructions)?;
+
+    let instruction = instructions
+        .get_instruction_relative(-1)
+        .map_err(|_| VaultError::InvalidSignature)?;
+
+    if instruction.get_program_id() != &ED25519_PROGRAM_ID {
+        return Err(VaultError::InvalidSignature.into());
+    }
+
+    let data = instruction.get_instruction_data();
+
+    if data.len() < HEADER_LEN + OFFSETS_LEN || data[0] != 1 {
+        return Err(VaultError::InvalidSignature.into());
+    }
+
+    // [signature offset, signature ix, public key offset, public key ix,
+    //  message offset, message size, message ix]
+    let offset = |i: usize| {
+        let start = HEADER_LEN + i * 2;
+        u16::from_le_bytes([data[start], data[start + 1]])
+    };
+
+    // Everything must be read from the Ed25519 instruction itself, otherwise
+    // the verified bytes could point at another instruction
+    if [offset(1), offset(3), offset(6)] != [CURRENT_INSTRUCTION; 3] {
+        return Err(VaultError::InvalidSignature.into());
+    }
+
+    let public_key_offset = offset(2) as usize;
+    let message_offset = offset(4) as usize;
+    let message_size = offset(5) as usize;
+
+    let public_key = data.get(public_key_offset..public_key_offset + 32);
+    let signed = data.get(message_offset..message_offset + message_size);
+
+    if public_key != Some(signer.as_ref()) || signed != Some(message) {
+        return Err(VaultError::InvalidSignature.into());
+    }
+
+    Ok(())
+}
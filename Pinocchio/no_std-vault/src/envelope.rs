@@ -0,0 +1,42 @@
+use pinocchio::error::ProgramError;
+
+use crate::VaultError;
+
+/// Set on the discriminator byte when an explicit version byte follows it
+pub const VERSIONED: u8 = 0x80;
+
+/// Payload version of the original `[discriminator, payload..]` encoding
+pub const LEGACY_VERSION: u8 = 0;
+
+/// Instruction data split into `[discriminator, version, payload..]`.
+///
+/// Data whose first byte has no [`VERSIONED`] bit is the original encoding
+/// and decodes as [`LEGACY_VERSION`], so `[0, amount]` and `[1]` keep
+/// working. With the bit set the next byte is the version:
+/// `[0x80, 0, amount]` is the same deposit as `[0, amount]`, while
+/// `[0x80, 1, ..]` selects the version 1 parser.
+pub struct Envelope<'a> {
+    pub discriminator: u8,
+    pub version: u8,
+    pub payload: &'a [u8],
+}
+
+impl<'a> TryFrom<&'a [u8]> for Envelope<'a> {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let Some((&discriminator, rest)) = data.split_first() else {
+            return Err(VaultError::InvalidInstruction.into());
+        };
+
+        if discriminator & VERSIONED == 0 {
+            return Ok(Self { discriminator, version: LEGACY_VERSION, payload: rest });
+        }
+
+        let Some((&version, payload)) = rest.split_first() else {
+            return Err(VaultError::InvalidInstructionData.into());
+        };
+
+        Ok(Self { discriminator: discriminator & !VERSIONED, version, payload })
+    }
+}
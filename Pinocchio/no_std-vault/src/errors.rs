@@ -0,0 +1,165 @@
+use pinocchio::error::ProgramError;
+
+/// Declares [`VaultError`] and its `TryFrom<u32>` decoder from a single list,
+/// so a new variant cannot be added to one and forgotten in the other
+macro_rules! vault_errors {
+    ($($(#[$doc:meta])* $variant:ident = $code:literal,)*) => {
+        /// Custom program errors, surfaced as `ProgramError::Custom(code)`.
+        ///
+        /// Codes are part of the program interface: never reorder or reuse them,
+        /// only append new variants.
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        #[repr(u32)]
+        pub enum VaultError {
+            $($(#[$doc])* $variant = $code,)*
+        }
+
+        impl TryFrom<u32> for VaultError {
+            type Error = u32;
+
+            fn try_from(code: u32) -> Result<Self, Self::Error> {
+                match code {
+                    $($code => Ok(VaultError::$variant),)*
+                    code => Err(code),
+                }
+            }
+        }
+    };
+}
+
+vault_errors! {
+    /// The vault account is not the `["vault", owner]` PDA
+    InvalidVault = 0,
+    /// A partial withdrawal would leave the vault below the rent-exempt minimum
+    BelowRentExempt = 1,
+    /// The state account is not the `["state", owner]` PDA of this vault
+    InvalidState = 2,
+    /// Withdrawals are not allowed before the vault's unlock time
+    VaultLocked = 3,
+    /// The new unlock time is earlier than the current one
+    LockShortened = 4,
+    /// The mint is not an initialized mint of the given token program
+    InvalidMint = 5,
+    /// The token account has the wrong mint or authority
+    InvalidTokenAccount = 6,
+    /// The multisig threshold or signer set is malformed
+    InvalidMultisig = 7,
+    /// Fewer distinct multisig signers than the threshold signed
+    NotEnoughSigners = 8,
+    /// The instruction was given fewer accounts than it requires
+    NotEnoughAccounts = 9,
+    /// The vault owner did not sign the transaction
+    OwnerNotSigner = 10,
+    /// The vault is not owned by the system program
+    InvalidVaultOwner = 11,
+    /// The system program account is not the system program
+    InvalidSystemProgram = 12,
+    /// The instruction data has the wrong length or layout
+    InvalidInstructionData = 13,
+    /// Deposit or withdrawal amount is zero
+    ZeroAmount = 14,
+    /// The vault holds no lamports to withdraw
+    VaultEmpty = 15,
+    /// The requested amount exceeds the vault balance
+    AmountExceedsBalance = 16,
+    /// The state account has not been initialized by this program
+    StateNotInitialized = 17,
+    /// The state account already exists
+    StateAlreadyInitialized = 18,
+    /// The token program is neither Token nor Token-2022
+    InvalidTokenProgram = 19,
+    /// The instruction discriminator is unknown
+    InvalidInstruction = 20,
+    /// A running total overflowed
+    Overflow = 21,
+    /// The signer is not the vault's beneficiary, or the beneficiary config is invalid
+    InvalidBeneficiary = 22,
+    /// The inactivity period since the owner's last action has not elapsed
+    OwnerStillActive = 23,
+    /// The beneficiary did not sign the claim
+    BeneficiaryNotSigner = 24,
+    /// The payer of a third-party deposit did not sign
+    PayerNotSigner = 25,
+    /// The hook program is invalid or missing from the deposit accounts
+    InvalidHook = 26,
+    /// The withdrawal recipient is read-only or the vault itself
+    InvalidRecipient = 27,
+    /// No matching Ed25519 signature by the owner precedes the instruction
+    InvalidSignature = 28,
+    /// The authorization nonce does not match the vault's next nonce
+    InvalidNonce = 29,
+    /// The signed authorization is past its expiry
+    AuthorizationExpired = 30,
+    /// The withdrawal allowlist has no free slot left
+    AllowlistFull = 31,
+    /// The allowlist entry is empty, already listed, not listed or the last one
+    InvalidAllowlistEntry = 32,
+    /// The withdrawal destination is not on the vault's allowlist
+    DestinationNotAllowed = 33,
+    /// The destination is allowlisted but its activation delay has not elapsed
+    AllowlistEntryPending = 34,
+    /// The withdrawal would exceed the vault's per-epoch cap
+    WithdrawLimitExceeded = 35,
+    /// The stream or its escrow does not match the owner and recipient, or
+    /// its schedule is invalid
+    InvalidStream = 36,
+    /// A stream between this owner and recipient already exists
+    StreamAlreadyExists = 37,
+    /// Nothing has vested since the last claim
+    NothingToClaim = 38,
+    /// The stream recipient did not sign the claim
+    RecipientNotSigner = 39,
+    /// The vault still has an allowlist, a withdrawal cap or a stake account,
+    /// or was initialized in the current slot
+    VaultNotClosable = 40,
+    /// The config account is not the config PDA or is in the wrong state
+    InvalidConfig = 41,
+    /// The deposit fee is above [`MAX_FEE_BPS`](crate::MAX_FEE_BPS) or the
+    /// depositor's `max_fee`
+    FeeTooHigh = 42,
+    /// The treasury account does not match the config
+    InvalidTreasury = 43,
+    /// The signer is not the config admin or the program upgrade authority
+    InvalidAdmin = 44,
+    /// The stake account is not the vault's stake PDA or is already in use
+    InvalidStakeAccount = 45,
+    /// The stake program account is not the native stake program
+    InvalidStakeProgram = 46,
+    /// The instruction has no parser for the requested payload version
+    UnsupportedVersion = 47,
+}
+
+impl From<VaultError> for ProgramError {
+    fn from(e: VaultError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl TryFrom<ProgramError> for VaultError {
+    type Error = ProgramError;
+
+    fn try_from(error: ProgramError) -> Result<Self, Self::Error> {
+        match error {
+            ProgramError::Custom(code) => VaultError::try_from(code).map_err(ProgramError::Custom),
+            error => Err(error),
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+impl VaultError {
+    /// Decodes the vault error carried by a failed transaction, if any
+    pub fn from_transaction_error(
+        error: &solana_transaction_error::TransactionError,
+    ) -> Option<Self> {
+        use solana_instruction::error::InstructionError;
+        use solana_transaction_error::TransactionError;
+
+        match error {
+            TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+                VaultError::try_from(*code).ok()
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use pinocchio::{address::Address, log::sol_log_data};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum VaultEventKind {
+    Deposit = 0,
+    Withdraw = 1,
+    Claim = 2,
+}
+
+impl TryFrom<u8> for VaultEventKind {
+    type Error = u8;
+
+    fn try_from(kind: u8) -> Result<Self, Self::Error> {
+        match kind {
+            0 => Ok(VaultEventKind::Deposit),
+            1 => Ok(VaultEventKind::Withdraw),
+            2 => Ok(VaultEventKind::Claim),
+            kind => Err(kind),
+        }
+    }
+}
+
+/// Binary event emitted through `sol_log_data` whenever lamports move.
+///
+/// Layout: `[kind (u8), owner (32 bytes), vault (32 bytes), amount (u64 LE),
+/// balance (u64 LE)]` where `balance` is the vault balance after the move.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VaultEvent {
+    pub kind: VaultEventKind,
+    pub owner: Address,
+    pub vault: Address,
+    pub amount: u64,
+    pub balance: u64,
+}
+
+impl VaultEvent {
+    pub const LEN: usize = 1 + 32 + 32 + 8 + 8;
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut data = [0u8; Self::LEN];
+
+        data[0] = self.kind as u8;
+        data[1..33].copy_from_slice(self.owner.as_ref());
+        data[33..65].copy_from_slice(self.vault.as_ref());
+        data[65..73].copy_from_slice(&self.amount.to_le_bytes());
+        data[73..81].copy_from_slice(&self.balance.to_le_bytes());
+
+        data
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::LEN {
+            return None;
+        }
+
+        Some(Self {
+            kind: VaultEventKind::try_from(data[0]).ok()?,
+            owner: Address::new_from_array(data[1..33].try_into().unwrap()),
+            vault: Address::new_from_array(data[33..65].try_into().unwrap()),
+            amount: u64::from_le_bytes(data[65..73].try_into().unwrap()),
+            balance: u64::from_le_bytes(data[73..81].try_into().unwrap()),
+        })
+    }
+
+    pub fn emit(&self) {
+        sol_log_data(&[&self.to_bytes()]);
+    }
+}
@@ -0,0 +1,348 @@
+#[cfg(test)]
+mod fuzz {
+    extern crate std;
+
+    use core::fmt::Debug;
+    use std::sync::OnceLock;
+    use std::vec;
+    use std::vec::Vec;
+
+    use crate::{
+        AddAllowlistEntryData, Config, ConfigureMultisigData, CreateStreamData, DelegateStakeData,
+        DepositData, Envelope, ExtendLockData, InitializeConfigData, InitializeData,
+        PartialWithdrawData, RemoveAllowlistEntryData, SetBeneficiaryData, SetHookData,
+        SetWithdrawCapData, Stream, UpdateConfigData, VaultEvent, VaultState, WithdrawSignedData,
+        STAKE_PROGRAM_ID, VERSIONED,
+    };
+    use litesvm::LiteSVM;
+    use pinocchio::address::Address;
+    use proptest::{collection, prelude::*};
+    use solana_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program,
+        sysvar::{self, clock::Clock},
+        transaction::{Transaction, TransactionError},
+    };
+
+    const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+    /// Covers every instruction plus some unknown discriminators
+    const MAX_DISCRIMINATOR: u8 = 32;
+
+    /// Number of keys in [`World::pool`]
+    const POOL_LEN: usize = 17;
+
+    /// Built by `cargo build-sbf`; without it only the host-side parser
+    /// property runs
+    const PROGRAM_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/deploy/blueshift_vault.so");
+
+    fn program_id() -> Pubkey {
+        Pubkey::new_from_array(crate::ID.to_bytes())
+    }
+
+    fn pda(seed: &[u8], owner: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[seed, owner.as_ref()], &program_id()).0
+    }
+
+    fn address(key: &Pubkey) -> Address {
+        Address::new_from_array(key.to_bytes())
+    }
+
+    /// The compiled program, read once; `None` with a note on stderr when it
+    /// has not been built
+    fn program() -> Option<&'static [u8]> {
+        static PROGRAM: OnceLock<Option<Vec<u8>>> = OnceLock::new();
+
+        PROGRAM
+            .get_or_init(|| {
+                let program = std::fs::read(PROGRAM_PATH).ok();
+                if program.is_none() {
+                    std::eprintln!("skipping LiteSVM fuzzing: {PROGRAM_PATH} is missing, run `cargo build-sbf` first");
+                }
+                program
+            })
+            .as_deref()
+    }
+
+    /// Two funded vaults, a config administered by the victim, a stream
+    /// from the victim and a pre-funded victim stake PDA; only the attacker
+    /// can sign
+    struct World {
+        svm: LiteSVM,
+        attacker: Keypair,
+        victim: Keypair,
+        pool: Vec<Pubkey>,
+        /// Accounts only the victim may debit or rewrite
+        guarded: Vec<Pubkey>,
+    }
+
+    impl World {
+        fn new(program: &[u8]) -> Self {
+            let mut svm = LiteSVM::new();
+            svm.add_program(program_id(), program);
+
+            let attacker = Keypair::new();
+            let victim = Keypair::new();
+            let treasury = Pubkey::new_unique();
+            let recipient = Pubkey::new_unique();
+
+            // Written directly rather than through InitializeConfig, which
+            // needs the program's upgrade authority
+            let (config, bump) = Pubkey::find_program_address(&[Config::SEED], &program_id());
+            let mut data = vec![0; Config::LEN];
+            Config::from_bytes_mut(&mut data)
+                .unwrap()
+                .init(&address(&victim.pubkey()), &address(&treasury), 100, bump)
+                .unwrap();
+            svm.set_account(
+                config,
+                Account {
+                    lamports: svm.minimum_balance_for_rent_exemption(Config::LEN),
+                    data,
+                    owner: program_id(),
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .unwrap();
+
+            for (user, amount) in [(&attacker, LAMPORTS_PER_SOL), (&victim, 2 * LAMPORTS_PER_SOL)] {
+                svm.airdrop(&user.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+                let mut initialize = vec![3];
+                initialize.extend_from_slice(&0i64.to_le_bytes());
+                let mut deposit = vec![0];
+                deposit.extend_from_slice(&amount.to_le_bytes());
+
+                let owner = user.pubkey();
+                let ixs = [
+                    Instruction {
+                        program_id: program_id(),
+                        accounts: vec![
+                            AccountMeta::new(owner, true),
+                            AccountMeta::new(pda(b"state", &owner), false),
+                            AccountMeta::new_readonly(system_program::ID, false),
+                        ],
+                        data: initialize,
+                    },
+                    Instruction {
+                        program_id: program_id(),
+                        accounts: vec![
+                            AccountMeta::new(owner, true),
+                            AccountMeta::new(pda(b"vault", &owner), false),
+                            AccountMeta::new_readonly(system_program::ID, false),
+                            AccountMeta::new(pda(b"state", &owner), false),
+                            AccountMeta::new_readonly(config, false),
+                            AccountMeta::new(treasury, false),
+                        ],
+                        data: deposit,
+                    },
+                ];
+
+                let tx = Transaction::new_signed_with_payer(&ixs, Some(&owner), &[user], svm.latest_blockhash());
+                svm.send_transaction(tx).unwrap();
+            }
+
+            // A stream to a recipient who never signs, so nothing can claim it
+            let victim_key = victim.pubkey();
+            let stream =
+                Pubkey::find_program_address(&[b"stream", victim_key.as_ref(), recipient.as_ref()], &program_id()).0;
+            let escrow = pda(b"stream_escrow", &stream);
+            let now = svm.get_sysvar::<Clock>().unix_timestamp;
+            let mut data = vec![17];
+            data.extend_from_slice(&(LAMPORTS_PER_SOL / 2).to_le_bytes());
+            data.extend_from_slice(&now.to_le_bytes());
+            data.extend_from_slice(&(now + 1_000_000).to_le_bytes());
+            let create_stream = Instruction {
+                program_id: program_id(),
+                accounts: vec![
+                    AccountMeta::new(victim_key, true),
+                    AccountMeta::new(recipient, false),
+                    AccountMeta::new(stream, false),
+                    AccountMeta::new(escrow, false),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+                data,
+            };
+            let tx =
+                Transaction::new_signed_with_payer(&[create_stream], Some(&victim_key), &[&victim], svm.latest_blockhash());
+            svm.send_transaction(tx).unwrap();
+
+            // Lamports parked on the victim's stake PDA before any delegation
+            let victim_stake = pda(b"stake", &victim_key);
+            svm.airdrop(&victim_stake, LAMPORTS_PER_SOL).unwrap();
+
+            let guarded = vec![
+                victim_key,
+                pda(b"vault", &victim_key),
+                pda(b"state", &victim_key),
+                config,
+                treasury,
+                stream,
+                escrow,
+                victim_stake,
+            ];
+
+            let mut pool = vec![
+                attacker.pubkey(),
+                pda(b"vault", &attacker.pubkey()),
+                pda(b"state", &attacker.pubkey()),
+                pda(b"stake", &attacker.pubkey()),
+                system_program::ID,
+                sysvar::instructions::ID,
+                Pubkey::new_from_array(STAKE_PROGRAM_ID.to_bytes()),
+                program_id(),
+                Pubkey::new_unique(),
+            ];
+            pool.extend_from_slice(&guarded);
+            assert_eq!(pool.len(), POOL_LEN);
+
+            Self { svm, attacker, victim, pool, guarded }
+        }
+
+        /// `(lamports, data)` of every guarded account
+        fn snapshot(&self) -> Vec<(u64, Vec<u8>)> {
+            self.guarded
+                .iter()
+                .map(|key| self.svm.get_account(key).map_or((0, Vec::new()), |account| (account.lamports, account.data)))
+                .collect()
+        }
+    }
+
+    /// What a deposit from someone else must leave alone in the victim's
+    /// state: everything but the deposit total and the activity slot
+    fn untouchable(data: &[u8]) -> impl PartialEq + Debug {
+        let state = VaultState::from_bytes(data).unwrap();
+
+        (
+            (state.owner, state.total_withdrawn(), state.unlock_timestamp(), state.signers().to_vec(), state.threshold),
+            (state.beneficiary().copied(), state.inactivity_period(), state.last_heartbeat(), state.hook().copied()),
+            (state.nonce(), state.init_slot(), state.allowlist().to_vec()),
+            (state.withdraw_cap(), state.pending_withdraw_cap()),
+            (state.limit_epoch(), state.epoch_withdrawn(), state.bump),
+        )
+    }
+
+    /// `(pool index, signer, writable)`; only the attacker can actually sign
+    fn accounts() -> impl Strategy<Value = Vec<(usize, bool, bool)>> {
+        collection::vec((0..POOL_LEN, any::<bool>(), any::<bool>()), 0..12)
+    }
+
+    /// Legacy and versioned envelopes alike
+    fn instruction_data() -> impl Strategy<Value = Vec<u8>> {
+        let discriminator =
+            prop_oneof![0..=MAX_DISCRIMINATOR, (0..=MAX_DISCRIMINATOR).prop_map(|d| d | VERSIONED)];
+        (discriminator, collection::vec(any::<u8>(), 0..96)).prop_map(|(discriminator, payload)| {
+            let mut data = vec![discriminator];
+            data.extend(payload);
+            data
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn instruction_data_parsers_never_panic(data in collection::vec(any::<u8>(), 0..128)) {
+            let data = data.as_slice();
+
+            let _ = Envelope::try_from(data);
+            let _ = DepositData::try_from(data);
+            let _ = DepositData::try_from_v1(data);
+            let _ = PartialWithdrawData::try_from(data);
+            let _ = InitializeData::try_from(data);
+            let _ = ExtendLockData::try_from(data);
+            let _ = ConfigureMultisigData::try_from(data);
+            let _ = SetBeneficiaryData::try_from(data);
+            let _ = SetHookData::try_from(data);
+            let _ = WithdrawSignedData::try_from(data);
+            let _ = AddAllowlistEntryData::try_from(data);
+            let _ = RemoveAllowlistEntryData::try_from(data);
+            let _ = SetWithdrawCapData::try_from(data);
+            let _ = CreateStreamData::try_from(data);
+            let _ = InitializeConfigData::try_from(data);
+            let _ = DelegateStakeData::try_from(data);
+            let _ = UpdateConfigData::try_from(data);
+            let _ = VaultEvent::from_bytes(data);
+            let _ = VaultState::from_bytes(data);
+            let _ = Stream::from_bytes(data);
+            let _ = Config::from_bytes(data);
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(128))]
+
+        #[test]
+        fn attacker_cannot_touch_victim_accounts(
+            accounts in accounts(),
+            data in instruction_data(),
+        ) {
+            let Some(program) = program() else {
+                return Ok(());
+            };
+
+            let mut world = World::new(program);
+            let attacker = world.attacker.pubkey();
+            let victim_state = pda(b"state", &world.victim.pubkey());
+
+            let accounts = accounts
+                .into_iter()
+                .map(|(index, signer, writable)| {
+                    let key = world.pool[index];
+                    let signer = signer && key == attacker;
+                    if writable { AccountMeta::new(key, signer) } else { AccountMeta::new_readonly(key, signer) }
+                })
+                .collect();
+
+            let ix = Instruction { program_id: program_id(), accounts, data };
+            let tx = Transaction::new_signed_with_payer(
+                &[ix],
+                Some(&attacker),
+                &[&world.attacker],
+                world.svm.latest_blockhash(),
+            );
+
+            let before = world.snapshot();
+            let result = world.svm.send_transaction(tx);
+            let after = world.snapshot();
+
+            // Never panics
+            let logs = match &result {
+                Ok(meta) => &meta.logs,
+                Err(failed) => &failed.meta.logs,
+            };
+            prop_assert!(!logs.iter().any(|log| log.contains("PANICKED") || log.contains("panicked")));
+
+            match result {
+                Err(failed) => {
+                    prop_assert!(!matches!(
+                        failed.err,
+                        TransactionError::InstructionError(_, InstructionError::ProgramFailedToComplete)
+                    ));
+                }
+                // Without the victim's signature, the victim's accounts keep
+                // their data and only ever gain lamports; a deposit into the
+                // victim's vault may only move its totals
+                Ok(_) => {
+                    for (key, ((lamports_before, data_before), (lamports_after, data_after))) in
+                        world.guarded.iter().zip(before.iter().zip(&after))
+                    {
+                        prop_assert!(lamports_after >= lamports_before, "{} lost lamports", key);
+
+                        if key == &victim_state {
+                            let state_before = VaultState::from_bytes(data_before).unwrap();
+                            let state_after = VaultState::from_bytes(data_after).unwrap();
+                            prop_assert!(state_after.total_deposited() >= state_before.total_deposited());
+                            prop_assert_eq!(untouchable(data_before), untouchable(data_after));
+                        } else {
+                            prop_assert_eq!(data_before, data_after, "{} changed", key);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
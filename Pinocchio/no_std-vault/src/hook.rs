@@ -0,0 +1,45 @@
+use pinocchio::{
+    account::AccountView,
+    cpi::invoke,
+    instruction::{InstructionAccount, InstructionView},
+    ProgramResult,
+};
+
+/// First 8 bytes of every deposit hook instruction
+pub const DEPOSIT_HOOK_DISCRIMINATOR: [u8; 8] = *b"vaultdep";
+
+/// Size of the deposit hook instruction data
+pub const DEPOSIT_HOOK_DATA_LEN: usize = 16;
+
+/// CPI into a vault's registered hook program after a deposit.
+///
+/// Instruction layout the hook program must accept:
+///
+/// - accounts: `[vault (readonly), depositor (readonly)]`
+/// - data: `[DEPOSIT_HOOK_DISCRIMINATOR (8 bytes), amount (u64 LE)]`
+///
+/// No signer privileges are forwarded, so the hook can only observe the
+/// deposit or reject it by returning an error.
+pub fn invoke_deposit_hook(
+    hook_program: &AccountView,
+    vault: &AccountView,
+    depositor: &AccountView,
+    amount: u64,
+) -> ProgramResult {
+    let instruction_accounts = [
+        InstructionAccount::readonly(vault.address()),
+        InstructionAccount::readonly(depositor.address()),
+    ];
+
+    let mut data = [0u8; DEPOSIT_HOOK_DATA_LEN];
+    data[..8].copy_from_slice(&DEPOSIT_HOOK_DISCRIMINATOR);
+    data[8..].copy_from_slice(&amount.to_le_bytes());
+
+    let instruction = InstructionView {
+        program_id: hook_program.address(),
+        accounts: &instruction_accounts,
+        data: &data,
+    };
+
+    invoke(&instruction, &[vault, depositor])
+}
@@ -0,0 +1,101 @@
+use pinocchio::{
+    account::AccountView,
+    address::Address,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{VaultError, VaultState};
+
+pub struct AllowlistAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub state: &'a AccountView,
+    pub remaining: &'a [AccountView],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for AllowlistAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, state, remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !owner.is_signer() {
+            return Err(VaultError::OwnerNotSigner.into());
+        }
+
+        // State check (vault must be initialized)
+        VaultState::check(state, owner.address())?;
+
+        Ok(Self { owner, state, remaining })
+    }
+}
+
+/// `[destination (32 bytes), activation_delay (i64 seconds)]`
+pub struct AddAllowlistEntryData {
+    pub destination: Address,
+    pub activation_delay: i64,
+}
+
+impl TryFrom<&[u8]> for AddAllowlistEntryData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != 32 + core::mem::size_of::<i64>() {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        let destination = Address::new_from_array(data[..32].try_into().unwrap());
+        let activation_delay = i64::from_le_bytes(data[32..].try_into().unwrap());
+
+        if activation_delay < 0 {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        Ok(Self { destination, activation_delay })
+    }
+}
+
+/// Approves a withdrawal destination; it can only be paid once
+/// `activation_delay` seconds have passed
+pub struct AddAllowlistEntry<'a> {
+    pub accounts: AllowlistAccounts<'a>,
+    pub data: AddAllowlistEntryData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for AddAllowlistEntry<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = AllowlistAccounts::try_from(accounts)?;
+        let data = AddAllowlistEntryData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> AddAllowlistEntry<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &14;
+
+    pub fn process(&self) -> ProgramResult {
+        let now = Clock::get()?.unix_timestamp;
+        let mut data = self.accounts.state.try_borrow_mut()?;
+        let state = VaultState::from_bytes_mut(&mut data)?;
+
+        // Vault configuration changes need the multisig threshold
+        state.check_signers(self.accounts.owner, self.accounts.remaining)?;
+
+        let active_at = now
+            .checked_add(self.data.activation_delay)
+            .ok_or(VaultError::Overflow)?;
+
+        state.add_allowlist_entry(&self.data.destination, active_at)?;
+        state.heartbeat(now);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{Stream, StreamAccounts};
+
+/// Ends a stream early: the recipient receives whatever has vested but is
+/// unclaimed, and the owner reclaims the unvested remainder and all rent
+pub struct CancelStream<'a> {
+    pub accounts: StreamAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CancelStream<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = StreamAccounts::try_from(accounts)?;
+
+        // Stream check (must be live)
+        Stream::check(accounts.stream, accounts.owner.address(), accounts.recipient.address())?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> CancelStream<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &19;
+
+    pub fn process(&self) -> ProgramResult {
+        let vested = {
+            let data = self.accounts.stream.try_borrow()?;
+            Stream::from_bytes(&data)?.claimable(Clock::get()?.unix_timestamp)
+        };
+
+        if vested != 0 {
+            self.accounts.pay_from_escrow(self.accounts.recipient, vested)?;
+        }
+
+        self.accounts.pay_from_escrow(self.accounts.owner, self.accounts.escrow.lamports())?;
+        Stream::close(self.accounts.stream, self.accounts.owner)
+    }
+}
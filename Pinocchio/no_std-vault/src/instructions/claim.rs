@@ -0,0 +1,128 @@
+use pinocchio::{
+    account::AccountView,
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use pinocchio_system::instructions::Transfer;
+
+use crate::{find_vault_address, VaultError, VaultEvent, VaultEventKind, VaultState};
+
+/// The owner does not sign: it is only used to derive the vault and state
+pub struct ClaimAccounts<'a> {
+    pub beneficiary: &'a AccountView,
+    pub owner: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub state: &'a AccountView,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for ClaimAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [beneficiary, owner, vault, system_program, state, _remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !beneficiary.is_signer() {
+            return Err(VaultError::BeneficiaryNotSigner.into());
+        }
+
+        // Owner check
+        if !vault.owned_by(&pinocchio_system::ID) {
+            return Err(VaultError::InvalidVaultOwner.into());
+        }
+
+        // Program ID check (prevents arbitrary CPI)
+        if system_program.address() != &pinocchio_system::ID {
+            return Err(VaultError::InvalidSystemProgram.into());
+        }
+
+        // PDA check (vault must belong to the given owner)
+        let (vault_address, bump) = find_vault_address(owner.address());
+        if vault.address() != &vault_address {
+            return Err(VaultError::InvalidVault.into());
+        }
+
+        // Check vault has lamports to claim
+        if vault.lamports() == 0 {
+            return Err(VaultError::VaultEmpty.into());
+        }
+
+        // State check (vault must be initialized)
+        VaultState::check(state, owner.address())?;
+
+        Ok(Self { beneficiary, owner, vault, system_program, state, bumps: [bump] })
+    }
+}
+
+pub struct Claim<'a> {
+    pub accounts: ClaimAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Claim<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = ClaimAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Claim<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &9;
+
+    pub fn process(&self) -> ProgramResult {
+        let amount = self.accounts.vault.lamports();
+
+        let clock = Clock::get()?;
+        let mut data = self.accounts.state.try_borrow_mut()?;
+        let state = VaultState::from_bytes_mut(&mut data)?;
+
+        // Time lock check
+        state.check_unlocked(clock.unix_timestamp)?;
+
+        // Dead man's switch check
+        state.check_claimable(self.accounts.beneficiary.address(), clock.unix_timestamp)?;
+
+        // Allowlist check
+        state.check_allowlisted(self.accounts.beneficiary.address(), clock.unix_timestamp)?;
+
+        let seeds = [
+            Seed::from(b"vault"),
+            Seed::from(self.accounts.owner.address().as_ref()),
+            Seed::from(&self.accounts.bumps),
+        ];
+
+        let signers = [Signer::from(&seeds)];
+
+        // Transfer all lamports from vault to beneficiary
+        Transfer {
+            from: self.accounts.vault,
+            to: self.accounts.beneficiary,
+            lamports: amount,
+        }
+        .invoke_signed(&signers)?;
+
+        // Not an owner action, so the heartbeat is left alone
+        state.record_withdrawal(amount, clock.slot)?;
+
+        VaultEvent {
+            kind: VaultEventKind::Claim,
+            owner: *self.accounts.owner.address(),
+            vault: *self.accounts.vault.address(),
+            amount,
+            balance: self.accounts.vault.lamports(),
+        }
+        .emit();
+
+        Ok(())
+    }
+}
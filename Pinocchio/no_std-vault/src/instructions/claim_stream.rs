@@ -0,0 +1,56 @@
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{Stream, StreamAccounts, VaultError};
+
+/// Pays the recipient everything vested so far. The final claim also returns
+/// the escrow reserve and the stream account's rent to the owner.
+pub struct ClaimStream<'a> {
+    pub accounts: StreamAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for ClaimStream<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = StreamAccounts::try_from_recipient(accounts)?;
+
+        // Stream check (must be live)
+        Stream::check(accounts.stream, accounts.owner.address(), accounts.recipient.address())?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> ClaimStream<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &18;
+
+    pub fn process(&self) -> ProgramResult {
+        let complete = {
+            let mut data = self.accounts.stream.try_borrow_mut()?;
+            let stream = Stream::from_bytes_mut(&mut data)?;
+
+            let amount = stream.claimable(Clock::get()?.unix_timestamp);
+            if amount == 0 {
+                return Err(VaultError::NothingToClaim.into());
+            }
+
+            self.accounts.pay_from_escrow(self.accounts.recipient, amount)?;
+            stream.record_claim(amount)?;
+
+            stream.is_complete()
+        };
+
+        if complete {
+            self.accounts.pay_from_escrow(self.accounts.owner, self.accounts.escrow.lamports())?;
+            Stream::close(self.accounts.stream, self.accounts.owner)?;
+        }
+
+        Ok(())
+    }
+}
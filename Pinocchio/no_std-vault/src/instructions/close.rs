@@ -0,0 +1,146 @@
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    find_stake_address, find_vault_address, is_unallocated, VaultError, VaultState,
+    WithdrawAccounts,
+};
+
+pub struct CloseAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub state: &'a AccountView,
+    pub stake: &'a AccountView,
+    pub remaining: &'a [AccountView],
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CloseAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, vault, system_program, state, stake, remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !owner.is_signer() {
+            return Err(VaultError::OwnerNotSigner.into());
+        }
+
+        // Owner check
+        if !vault.owned_by(&pinocchio_system::ID) {
+            return Err(VaultError::InvalidVaultOwner.into());
+        }
+
+        // Program ID check (prevents arbitrary CPI)
+        if system_program.address() != &pinocchio_system::ID {
+            return Err(VaultError::InvalidSystemProgram.into());
+        }
+
+        // PDA check (vault must belong to the signing owner)
+        let (vault_address, bump) = find_vault_address(owner.address());
+        if vault.address() != &vault_address {
+            return Err(VaultError::InvalidVault.into());
+        }
+
+        // PDA check (stake account must belong to the signing owner)
+        if stake.address() != &find_stake_address(owner.address()).0 {
+            return Err(VaultError::InvalidStakeAccount.into());
+        }
+
+        // State check (vault must be initialized)
+        VaultState::check(state, owner.address())?;
+
+        Ok(Self { owner, vault, system_program, state, stake, remaining, bumps: [bump] })
+    }
+}
+
+/// Retires a vault: sweeps its lamports to the owner, then wipes the state
+/// account and hands it back to the system program with its rent going to
+/// the owner. The vault has to be initialized again before the next deposit.
+///
+/// Closing is refused while the vault is locked, has an allowlist, a
+/// withdrawal cap or a stake account, or was initialized in the current
+/// slot, so a re-initialization can never be used to get around them.
+/// Accounts are `[owner, vault, system_program, state, stake, signers..]`.
+pub struct Close<'a> {
+    pub accounts: CloseAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Close<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = CloseAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Close<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &20;
+
+    pub fn process(&self) -> ProgramResult {
+        let clock = Clock::get()?;
+        let balance = self.accounts.vault.lamports();
+
+        {
+            let data = self.accounts.state.try_borrow()?;
+            let state = VaultState::from_bytes(&data)?;
+
+            // Closable check (a fresh state must not shed the old protections)
+            state.check_closable(clock.slot)?;
+
+            // Stake check (staked lamports must come back under the old state)
+            if !is_unallocated(self.accounts.stake) {
+                return Err(VaultError::VaultNotClosable.into());
+            }
+
+            if balance == 0 {
+                // An empty vault skips the sweep, so its checks run here
+                state.check_unlocked(clock.unix_timestamp)?;
+                state.check_signers(self.accounts.owner, self.accounts.remaining)?;
+            }
+        }
+
+        if balance != 0 {
+            // Sweeping the vault goes through every regular withdraw check
+            WithdrawAccounts {
+                owner: self.accounts.owner,
+                vault: self.accounts.vault,
+                system_program: self.accounts.system_program,
+                state: self.accounts.state,
+                remaining: self.accounts.remaining,
+                bumps: self.accounts.bumps,
+            }
+            .withdraw(self.accounts.owner, balance)?;
+        }
+
+        // Wipe the state so nothing survives a later re-initialization
+        self.accounts.state.try_borrow_mut()?.fill(0);
+
+        // Return the state rent to the owner
+        let lamports = self
+            .accounts
+            .owner
+            .lamports()
+            .checked_add(self.accounts.state.lamports())
+            .ok_or(VaultError::Overflow)?;
+
+        self.accounts.owner.set_lamports(lamports);
+        self.accounts.state.set_lamports(0);
+
+        // Hand the emptied account back to the system program
+        self.accounts.state.resize(0)?;
+        unsafe { self.accounts.state.assign(&pinocchio_system::ID) };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use pinocchio::{
+    account::AccountView,
+    address::Address,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{VaultError, VaultState, MAX_SIGNERS};
+
+pub struct ConfigureMultisigAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub state: &'a AccountView,
+    pub remaining: &'a [AccountView],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for ConfigureMultisigAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, state, remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !owner.is_signer() {
+            return Err(VaultError::OwnerNotSigner.into());
+        }
+
+        // State check (vault must be initialized)
+        VaultState::check(state, owner.address())?;
+
+        Ok(Self { owner, state, remaining })
+    }
+}
+
+/// `[threshold, signer_0 (32 bytes), signer_1 (32 bytes), ...]`
+pub struct ConfigureMultisigData<'a> {
+    pub threshold: u8,
+    pub signers: &'a [Address],
+}
+
+impl<'a> TryFrom<&'a [u8]> for ConfigureMultisigData<'a> {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let Some((threshold, signers)) = data.split_first() else {
+            return Err(VaultError::InvalidInstructionData.into());
+        };
+
+        if signers.len() % 32 != 0 || signers.len() / 32 > MAX_SIGNERS {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        // Safe: `Address` is a byte-aligned 32-byte array
+        let signers = unsafe {
+            core::slice::from_raw_parts(signers.as_ptr() as *const Address, signers.len() / 32)
+        };
+
+        Ok(Self { threshold: *threshold, signers })
+    }
+}
+
+pub struct ConfigureMultisig<'a> {
+    pub accounts: ConfigureMultisigAccounts<'a>,
+    pub data: ConfigureMultisigData<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for ConfigureMultisig<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = ConfigureMultisigAccounts::try_from(accounts)?;
+        let data = ConfigureMultisigData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> ConfigureMultisig<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &7;
+
+    pub fn process(&self) -> ProgramResult {
+        let mut data = self.accounts.state.try_borrow_mut()?;
+        let state = VaultState::from_bytes_mut(&mut data)?;
+
+        // Changing an existing signer set needs the current threshold, so a
+        // single compromised owner key cannot lower it
+        state.check_signers(self.accounts.owner, self.accounts.remaining)?;
+
+        state.set_multisig(self.data.threshold, self.data.signers)?;
+        state.heartbeat(Clock::get()?.unix_timestamp);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+use pinocchio::{
+    account::AccountView,
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use pinocchio_system::instructions::Transfer;
+
+use crate::{
+    create_pda_account, find_stream_address, find_stream_escrow_address, is_unallocated, Stream,
+    VaultError,
+};
+
+/// Accounts shared by every stream instruction:
+/// `[owner, recipient, stream, escrow, system_program]`
+pub struct StreamAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub recipient: &'a AccountView,
+    pub stream: &'a AccountView,
+    pub escrow: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub bumps: [u8; 1],
+    pub escrow_bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for StreamAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, recipient, stream, escrow, system_program, _remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !owner.is_signer() {
+            return Err(VaultError::OwnerNotSigner.into());
+        }
+
+        Self::new(owner, recipient, stream, escrow, system_program)
+    }
+}
+
+impl<'a> StreamAccounts<'a> {
+    /// Parses the same layout with the recipient as the signer
+    pub fn try_from_recipient(accounts: &'a [AccountView]) -> Result<Self, ProgramError> {
+        let [owner, recipient, stream, escrow, system_program, _remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !recipient.is_signer() {
+            return Err(VaultError::RecipientNotSigner.into());
+        }
+
+        Self::new(owner, recipient, stream, escrow, system_program)
+    }
+
+    fn new(
+        owner: &'a AccountView,
+        recipient: &'a AccountView,
+        stream: &'a AccountView,
+        escrow: &'a AccountView,
+        system_program: &'a AccountView,
+    ) -> Result<Self, ProgramError> {
+        // Program ID check (prevents arbitrary CPI)
+        if system_program.address() != &pinocchio_system::ID {
+            return Err(VaultError::InvalidSystemProgram.into());
+        }
+
+        // PDA check (stream must belong to this owner and recipient)
+        let (stream_address, bump) = find_stream_address(owner.address(), recipient.address());
+        if stream.address() != &stream_address {
+            return Err(VaultError::InvalidStream.into());
+        }
+
+        // PDA check (escrow must belong to the stream)
+        let (escrow_address, escrow_bump) = find_stream_escrow_address(stream.address());
+        if escrow.address() != &escrow_address {
+            return Err(VaultError::InvalidStream.into());
+        }
+
+        Ok(Self {
+            owner,
+            recipient,
+            stream,
+            escrow,
+            system_program,
+            bumps: [bump],
+            escrow_bumps: [escrow_bump],
+        })
+    }
+
+    /// Pays `amount` lamports out of the escrow
+    pub fn pay_from_escrow(&self, to: &AccountView, amount: u64) -> ProgramResult {
+        let seeds = [
+            Seed::from(Stream::ESCROW_SEED),
+            Seed::from(self.stream.address().as_ref()),
+            Seed::from(&self.escrow_bumps),
+        ];
+
+        let signers = [Signer::from(&seeds)];
+
+        Transfer {
+            from: self.escrow,
+            to,
+            lamports: amount,
+        }
+        .invoke_signed(&signers)
+    }
+}
+
+/// `[amount (u64), start_time (i64), end_time (i64)]`
+pub struct CreateStreamData {
+    pub amount: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+impl TryFrom<&[u8]> for CreateStreamData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != 24 {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let start_time = i64::from_le_bytes(data[8..16].try_into().unwrap());
+        let end_time = i64::from_le_bytes(data[16..24].try_into().unwrap());
+
+        // Validate amount is not zero
+        if amount == 0 {
+            return Err(VaultError::ZeroAmount.into());
+        }
+
+        if end_time <= start_time {
+            return Err(VaultError::InvalidStream.into());
+        }
+
+        Ok(Self { amount, start_time, end_time })
+    }
+}
+
+/// Locks `amount` lamports from the owner into a stream that vests linearly
+/// to the recipient between `start_time` and `end_time`
+pub struct CreateStream<'a> {
+    pub accounts: StreamAccounts<'a>,
+    pub data: CreateStreamData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for CreateStream<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = StreamAccounts::try_from(accounts)?;
+        let data = CreateStreamData::try_from(data)?;
+
+        // Stream must not exist yet (lamports alone don't count, anyone can
+        // pre-fund the PDA)
+        if !is_unallocated(accounts.stream) {
+            return Err(VaultError::StreamAlreadyExists.into());
+        }
+
+        // The escrow only ever holds lamports
+        if !is_unallocated(accounts.escrow) {
+            return Err(VaultError::InvalidStream.into());
+        }
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> CreateStream<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &17;
+
+    pub fn process(&self) -> ProgramResult {
+        let rent = Rent::get()?;
+
+        let seeds = [
+            Seed::from(Stream::SEED),
+            Seed::from(self.accounts.owner.address().as_ref()),
+            Seed::from(self.accounts.recipient.address().as_ref()),
+            Seed::from(&self.accounts.bumps),
+        ];
+
+        let signers = [Signer::from(&seeds)];
+
+        // Create the program-owned stream account at the PDA
+        create_pda_account(
+            self.accounts.owner,
+            self.accounts.stream,
+            rent.minimum_balance(Stream::LEN),
+            Stream::LEN as u64,
+            &crate::ID,
+            &signers,
+        )?;
+
+        let mut data = self.accounts.stream.try_borrow_mut()?;
+        Stream::from_bytes_mut(&mut data)?.init(
+            self.accounts.owner.address(),
+            self.accounts.recipient.address(),
+            self.data.amount,
+            self.data.start_time,
+            self.data.end_time,
+            self.accounts.bumps[0],
+            self.accounts.escrow_bumps[0],
+        );
+
+        // The escrow also keeps a rent-exempt reserve so partial claims never
+        // leave it below the minimum; the reserve goes back to the owner
+        let reserve = rent.minimum_balance(0);
+        let target = self.data.amount.checked_add(reserve).ok_or(VaultError::Overflow)?;
+
+        // Lamports already sitting in the escrow count towards it, and go
+        // back to the owner with the reserve
+        let shortfall = target.saturating_sub(self.accounts.escrow.lamports());
+        if shortfall == 0 {
+            return Ok(());
+        }
+
+        Transfer {
+            from: self.accounts.owner,
+            to: self.accounts.escrow,
+            lamports: shortfall,
+        }
+        .invoke()
+    }
+}
@@ -0,0 +1,43 @@
+use pinocchio::{
+    account::AccountView,
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{deactivate_stake, StakeAccounts};
+
+/// Starts cooling down the vault's delegated stake so it can be withdrawn
+/// back with [`WithdrawStake`](crate::WithdrawStake)
+pub struct DeactivateStake<'a> {
+    pub accounts: StakeAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for DeactivateStake<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = StakeAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> DeactivateStake<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &24;
+
+    pub fn process(&self) -> ProgramResult {
+        self.accounts.authorize()?;
+
+        let seeds = [
+            Seed::from(b"vault"),
+            Seed::from(self.accounts.owner.address().as_ref()),
+            Seed::from(&self.accounts.bumps),
+        ];
+
+        let signers = [Signer::from(&seeds)];
+
+        deactivate_stake(self.accounts.stake, self.accounts.clock, self.accounts.vault, &signers)
+    }
+}
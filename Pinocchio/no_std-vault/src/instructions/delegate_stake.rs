@@ -0,0 +1,229 @@
+use pinocchio::{
+    account::AccountView,
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    create_pda_account, delegate_stake, find_stake_address, find_vault_address, initialize_stake,
+    is_unallocated, VaultError, VaultState, STAKE_ACCOUNT_LEN, STAKE_PROGRAM_ID, STAKE_SEED,
+};
+
+/// Accounts shared by every stake instruction:
+/// `[owner, vault, state, stake, stake_program, clock, signers..]`.
+///
+/// The vault PDA is both the stake and the withdraw authority of the stake
+/// account, so staked lamports can only ever come back to the vault.
+pub struct StakeAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub state: &'a AccountView,
+    pub stake: &'a AccountView,
+    pub stake_program: &'a AccountView,
+    pub clock: &'a AccountView,
+    pub remaining: &'a [AccountView],
+    pub bumps: [u8; 1],
+    pub stake_bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for StakeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, vault, state, stake, stake_program, clock, remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !owner.is_signer() {
+            return Err(VaultError::OwnerNotSigner.into());
+        }
+
+        // Owner check
+        if !vault.owned_by(&pinocchio_system::ID) {
+            return Err(VaultError::InvalidVaultOwner.into());
+        }
+
+        // Program ID check (the vault signs every stake CPI)
+        if stake_program.address() != &STAKE_PROGRAM_ID {
+            return Err(VaultError::InvalidStakeProgram.into());
+        }
+
+        // PDA check (vault must belong to the signing owner)
+        let (vault_address, bump) = find_vault_address(owner.address());
+        if vault.address() != &vault_address {
+            return Err(VaultError::InvalidVault.into());
+        }
+
+        // PDA check (stake account must belong to the signing owner)
+        let (stake_address, stake_bump) = find_stake_address(owner.address());
+        if stake.address() != &stake_address {
+            return Err(VaultError::InvalidStakeAccount.into());
+        }
+
+        // State check (vault must be initialized)
+        VaultState::check(state, owner.address())?;
+
+        Ok(Self {
+            owner,
+            vault,
+            state,
+            stake,
+            stake_program,
+            clock,
+            remaining,
+            bumps: [bump],
+            stake_bumps: [stake_bump],
+        })
+    }
+}
+
+impl StakeAccounts<'_> {
+    /// Multisig check, then records the owner's activity
+    pub fn authorize(&self) -> ProgramResult {
+        let clock = Clock::get()?;
+        let mut data = self.state.try_borrow_mut()?;
+        let state = VaultState::from_bytes_mut(&mut data)?;
+
+        // Multisig check
+        state.check_signers(self.owner, self.remaining)?;
+
+        state.set_last_activity_slot(clock.slot);
+        state.heartbeat(clock.unix_timestamp);
+
+        Ok(())
+    }
+}
+
+pub struct DelegateStakeData {
+    pub amount: u64,
+}
+
+impl TryFrom<&[u8]> for DelegateStakeData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != core::mem::size_of::<u64>() {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+        // Validate amount is not zero
+        if amount == 0 {
+            return Err(VaultError::ZeroAmount.into());
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+/// Moves `amount` lamports from the vault into its stake account and
+/// delegates them to `vote`. Accounts are `[vote, stake_history,
+/// stake_config, rent, system_program, owner, vault, state, stake,
+/// stake_program, clock, signers..]`.
+///
+/// The lamports stay under the vault's control, so the time lock,
+/// allowlist and rate limit only apply once they are withdrawn from the
+/// vault itself.
+pub struct DelegateStake<'a> {
+    pub vote: &'a AccountView,
+    pub stake_history: &'a AccountView,
+    pub stake_config: &'a AccountView,
+    pub rent: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub accounts: StakeAccounts<'a>,
+    pub data: DelegateStakeData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for DelegateStake<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let [vote, stake_history, stake_config, rent, system_program, accounts @ ..] = accounts
+        else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        let accounts = StakeAccounts::try_from(accounts)?;
+        let data = DelegateStakeData::try_from(data)?;
+
+        // Program ID check (prevents arbitrary CPI)
+        if system_program.address() != &pinocchio_system::ID {
+            return Err(VaultError::InvalidSystemProgram.into());
+        }
+
+        // Stake check (one delegation per vault at a time); lamports sent to
+        // the PDA beforehand do not count
+        if !is_unallocated(accounts.stake) {
+            return Err(VaultError::InvalidStakeAccount.into());
+        }
+
+        Ok(Self { vote, stake_history, stake_config, rent, system_program, accounts, data })
+    }
+}
+
+impl<'a> DelegateStake<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &23;
+
+    pub fn process(&self) -> ProgramResult {
+        self.accounts.authorize()?;
+
+        // Cannot stake more than the vault holds
+        let remaining = self
+            .accounts
+            .vault
+            .lamports()
+            .checked_sub(self.data.amount)
+            .ok_or(VaultError::AmountExceedsBalance)?;
+
+        // Whatever stays behind must keep the vault rent exempt
+        if remaining != 0 && remaining < Rent::get()?.minimum_balance(0) {
+            return Err(VaultError::BelowRentExempt.into());
+        }
+
+        let vault_seeds = [
+            Seed::from(b"vault"),
+            Seed::from(self.accounts.owner.address().as_ref()),
+            Seed::from(&self.accounts.bumps),
+        ];
+        let stake_seeds = [
+            Seed::from(STAKE_SEED),
+            Seed::from(self.accounts.owner.address().as_ref()),
+            Seed::from(&self.accounts.stake_bumps),
+        ];
+
+        // The vault moves the full amount into the stake account, including
+        // its rent, on top of anything already sent to the PDA
+        let lamports = self
+            .accounts
+            .stake
+            .lamports()
+            .checked_add(self.data.amount)
+            .ok_or(VaultError::Overflow)?;
+
+        create_pda_account(
+            self.accounts.vault,
+            self.accounts.stake,
+            lamports,
+            STAKE_ACCOUNT_LEN as u64,
+            &STAKE_PROGRAM_ID,
+            &[Signer::from(&vault_seeds), Signer::from(&stake_seeds)],
+        )?;
+
+        initialize_stake(self.accounts.stake, self.rent, self.accounts.vault.address())?;
+
+        delegate_stake(
+            self.accounts.stake,
+            self.vote,
+            self.accounts.clock,
+            self.stake_history,
+            self.stake_config,
+            self.accounts.vault,
+            &[Signer::from(&vault_seeds)],
+        )
+    }
+}
@@ -0,0 +1,263 @@
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use pinocchio_system::instructions::Transfer;
+
+use crate::{
+    find_config_address, find_vault_address, invoke_deposit_hook, Config, VaultError, VaultEvent,
+    VaultEventKind, VaultState,
+};
+
+/// `payer` funds the deposit; it is the owner itself unless the deposit is
+/// made on the owner's behalf through [`DepositFor`](crate::DepositFor).
+/// `config` is the global fee config and `treasury` receives the protocol fee
+/// once the config is initialized.
+/// When the vault has a deposit hook, its program is the first remaining account.
+pub struct DepositAccounts<'a> {
+    pub payer: &'a AccountView,
+    pub owner: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub state: &'a AccountView,
+    pub config: &'a AccountView,
+    pub treasury: &'a AccountView,
+    pub remaining: &'a [AccountView],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for DepositAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, vault, system_program, state, config, treasury, remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !owner.is_signer() {
+            return Err(VaultError::OwnerNotSigner.into());
+        }
+
+        Self::new(owner, owner, vault, system_program, state, config, treasury, remaining)
+    }
+}
+
+impl<'a> DepositAccounts<'a> {
+    /// Parses the third-party layout
+    /// `[payer, owner, vault, system_program, state, config, treasury]` where
+    /// only the payer signs
+    pub fn try_from_payer(accounts: &'a [AccountView]) -> Result<Self, ProgramError> {
+        let [payer, owner, vault, system_program, state, config, treasury, remaining @ ..] =
+            accounts
+        else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !payer.is_signer() {
+            return Err(VaultError::PayerNotSigner.into());
+        }
+
+        Self::new(payer, owner, vault, system_program, state, config, treasury, remaining)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        payer: &'a AccountView,
+        owner: &'a AccountView,
+        vault: &'a AccountView,
+        system_program: &'a AccountView,
+        state: &'a AccountView,
+        config: &'a AccountView,
+        treasury: &'a AccountView,
+        remaining: &'a [AccountView],
+    ) -> Result<Self, ProgramError> {
+        // Owner check using proper method name
+        if !vault.owned_by(&pinocchio_system::ID) {
+            return Err(VaultError::InvalidVaultOwner.into());
+        }
+
+        // Program ID check (prevents arbitrary CPI)
+        if system_program.address() != &pinocchio_system::ID {
+            return Err(VaultError::InvalidSystemProgram.into());
+        }
+
+        // PDA check (vault must belong to the owner)
+        let (vault_address, _) = find_vault_address(owner.address());
+        if vault.address() != &vault_address {
+            return Err(VaultError::InvalidVault.into());
+        }
+
+        // State check (vault must be initialized)
+        VaultState::check(state, owner.address())?;
+
+        // PDA check (config is a single global account)
+        if config.address() != &find_config_address().0 {
+            return Err(VaultError::InvalidConfig.into());
+        }
+
+        Ok(Self { payer, owner, vault, system_program, state, config, treasury, remaining })
+    }
+
+    /// Protocol fee owed on a deposit of `amount` at `now`; zero until the
+    /// config is initialized
+    fn protocol_fee(&self, amount: u64, now: i64) -> Result<u64, ProgramError> {
+        if !self.config.owned_by(&crate::ID) {
+            return Ok(0);
+        }
+
+        let data = self.config.try_borrow()?;
+        let config = Config::from_bytes(&data)?;
+
+        // Treasury check (fee must go where the admin configured it)
+        if self.treasury.address() != &config.treasury {
+            return Err(VaultError::InvalidTreasury.into());
+        }
+
+        config.fee(amount, now)
+    }
+
+    /// Moves `amount` from the payer, minus the protocol fee which goes to the
+    /// treasury, into the vault and records the net deposit. Fails when the
+    /// fee exceeds `max_fee`, if the depositor set one.
+    pub fn deposit(&self, amount: u64, max_fee: Option<u64>) -> ProgramResult {
+        let clock = Clock::get()?;
+        let fee = self.protocol_fee(amount, clock.unix_timestamp)?;
+        if max_fee.is_some_and(|max_fee| fee > max_fee) {
+            return Err(VaultError::FeeTooHigh.into());
+        }
+        let amount = amount.checked_sub(fee).ok_or(VaultError::Overflow)?;
+
+        Transfer {
+            from: self.payer,
+            to: self.vault,
+            lamports: amount,
+        }
+        .invoke()?;
+
+        if fee > 0 {
+            Transfer {
+                from: self.payer,
+                to: self.treasury,
+                lamports: fee,
+            }
+            .invoke()?;
+        }
+
+        let mut data = self.state.try_borrow_mut()?;
+        let state = VaultState::from_bytes_mut(&mut data)?;
+
+        // Record the deposit in the vault history
+        state.record_deposit(amount, clock.slot)?;
+
+        // Only the owner's own deposits count as a sign of life
+        if self.payer.address() == self.owner.address() {
+            state.heartbeat(clock.unix_timestamp);
+        }
+
+        let hook = state.hook().copied();
+        drop(data);
+
+        VaultEvent {
+            kind: VaultEventKind::Deposit,
+            owner: *self.owner.address(),
+            vault: *self.vault.address(),
+            amount,
+            balance: self.vault.lamports(),
+        }
+        .emit();
+
+        // Notify the registered hook; its failure fails the deposit
+        if let Some(hook) = hook {
+            let Some(hook_program) = self.remaining.first() else {
+                return Err(VaultError::InvalidHook.into());
+            };
+            if hook_program.address() != &hook {
+                return Err(VaultError::InvalidHook.into());
+            }
+
+            invoke_deposit_hook(hook_program, self.vault, self.payer, amount)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Version 0: `[amount (u64)]`.
+/// Version 1: `[amount (u64), max_fee (u64, optional)]`.
+pub struct DepositData {
+    pub amount: u64,
+    pub max_fee: Option<u64>,
+}
+
+impl TryFrom<&[u8]> for DepositData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != core::mem::size_of::<u64>() {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        Self::new(u64::from_le_bytes(data.try_into().unwrap()), None)
+    }
+}
+
+impl DepositData {
+    /// Parses the version 1 payload
+    pub fn try_from_v1(data: &[u8]) -> Result<Self, ProgramError> {
+        let (amount, max_fee) = match data.len() {
+            8 => (data, None),
+            16 => (&data[..8], Some(u64::from_le_bytes(data[8..].try_into().unwrap()))),
+            _ => return Err(VaultError::InvalidInstructionData.into()),
+        };
+
+        Self::new(u64::from_le_bytes(amount.try_into().unwrap()), max_fee)
+    }
+
+    fn new(amount: u64, max_fee: Option<u64>) -> Result<Self, ProgramError> {
+        // Validate amount is not zero
+        if amount == 0 {
+            return Err(VaultError::ZeroAmount.into());
+        }
+
+        Ok(Self { amount, max_fee })
+    }
+}
+
+pub struct Deposit<'a> {
+    pub accounts: DepositAccounts<'a>,
+    pub data: DepositData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Deposit<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = DepositAccounts::try_from(accounts)?;
+        let data = DepositData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> Deposit<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &0;
+
+    /// Same accounts, version 1 instruction data
+    pub fn try_from_v1(
+        (data, accounts): (&'a [u8], &'a [AccountView]),
+    ) -> Result<Self, ProgramError> {
+        let accounts = DepositAccounts::try_from(accounts)?;
+        let data = DepositData::try_from_v1(data)?;
+
+        Ok(Self { accounts, data })
+    }
+
+    pub fn process(&self) -> ProgramResult {
+        self.accounts.deposit(self.data.amount, self.data.max_fee)
+    }
+}
@@ -0,0 +1,40 @@
+use pinocchio::{account::AccountView, error::ProgramError, ProgramResult};
+
+use crate::{DepositAccounts, DepositData};
+
+/// Deposit funded by any signer into `owner`'s vault; only the owner can
+/// ever withdraw it
+pub struct DepositFor<'a> {
+    pub accounts: DepositAccounts<'a>,
+    pub data: DepositData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for DepositFor<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = DepositAccounts::try_from_payer(accounts)?;
+        let data = DepositData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> DepositFor<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &10;
+
+    /// Same accounts, version 1 instruction data
+    pub fn try_from_v1(
+        (data, accounts): (&'a [u8], &'a [AccountView]),
+    ) -> Result<Self, ProgramError> {
+        let accounts = DepositAccounts::try_from_payer(accounts)?;
+        let data = DepositData::try_from_v1(data)?;
+
+        Ok(Self { accounts, data })
+    }
+
+    pub fn process(&self) -> ProgramResult {
+        self.accounts.deposit(self.data.amount, self.data.max_fee)
+    }
+}
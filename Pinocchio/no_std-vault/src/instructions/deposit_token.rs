@@ -0,0 +1,127 @@
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    find_vault_address, transfer_checked, DepositData, MintInterface, TokenAccountInterface,
+    TokenProgram, VaultError, VaultState,
+};
+
+/// Accounts shared by token deposits and withdrawals. The vault token
+/// account is any token account whose authority is the `["vault", owner]` PDA.
+pub struct TokenVaultAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub state: &'a AccountView,
+    pub mint: &'a AccountView,
+    pub owner_token_account: &'a AccountView,
+    pub vault_token_account: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub remaining: &'a [AccountView],
+    pub decimals: u8,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for TokenVaultAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, vault, state, mint, owner_token_account, vault_token_account, token_program, remaining @ ..] =
+            accounts
+        else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !owner.is_signer() {
+            return Err(VaultError::OwnerNotSigner.into());
+        }
+
+        // Program ID check (prevents arbitrary CPI)
+        TokenProgram::check(token_program)?;
+
+        // PDA check (vault must belong to the signing owner)
+        let (vault_address, bump) = find_vault_address(owner.address());
+        if vault.address() != &vault_address {
+            return Err(VaultError::InvalidVault.into());
+        }
+
+        // State check (vault must be initialized)
+        VaultState::check(state, owner.address())?;
+
+        // Mint and token account checks
+        let decimals = MintInterface::check(mint, token_program.address())?;
+        TokenAccountInterface::check(
+            owner_token_account,
+            token_program.address(),
+            mint.address(),
+            owner.address(),
+        )?;
+        TokenAccountInterface::check(
+            vault_token_account,
+            token_program.address(),
+            mint.address(),
+            vault.address(),
+        )?;
+
+        Ok(Self {
+            owner,
+            vault,
+            state,
+            mint,
+            owner_token_account,
+            vault_token_account,
+            token_program,
+            remaining,
+            decimals,
+            bumps: [bump],
+        })
+    }
+}
+
+pub struct DepositToken<'a> {
+    pub accounts: TokenVaultAccounts<'a>,
+    pub data: DepositData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for DepositToken<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = TokenVaultAccounts::try_from(accounts)?;
+        let data = DepositData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> DepositToken<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &5;
+
+    pub fn process(&self) -> ProgramResult {
+        // Owner signs for their own token account
+        transfer_checked(
+            self.accounts.token_program,
+            self.accounts.owner_token_account,
+            self.accounts.mint,
+            self.accounts.vault_token_account,
+            self.accounts.owner,
+            self.data.amount,
+            self.accounts.decimals,
+            &[],
+        )?;
+
+        let clock = Clock::get()?;
+        let mut data = self.accounts.state.try_borrow_mut()?;
+        let state = VaultState::from_bytes_mut(&mut data)?;
+
+        state.set_last_activity_slot(clock.slot);
+        state.heartbeat(clock.unix_timestamp);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{VaultError, VaultState};
+
+pub struct ExtendLockAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub state: &'a AccountView,
+    pub remaining: &'a [AccountView],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for ExtendLockAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, state, remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !owner.is_signer() {
+            return Err(VaultError::OwnerNotSigner.into());
+        }
+
+        // State check (vault must be initialized)
+        VaultState::check(state, owner.address())?;
+
+        Ok(Self { owner, state, remaining })
+    }
+}
+
+pub struct ExtendLockData {
+    pub unlock_timestamp: i64,
+}
+
+impl TryFrom<&[u8]> for ExtendLockData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != core::mem::size_of::<i64>() {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        let unlock_timestamp = i64::from_le_bytes(data.try_into().unwrap());
+
+        Ok(Self { unlock_timestamp })
+    }
+}
+
+pub struct ExtendLock<'a> {
+    pub accounts: ExtendLockAccounts<'a>,
+    pub data: ExtendLockData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for ExtendLock<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = ExtendLockAccounts::try_from(accounts)?;
+        let data = ExtendLockData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> ExtendLock<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &4;
+
+    pub fn process(&self) -> ProgramResult {
+        let mut data = self.accounts.state.try_borrow_mut()?;
+        let state = VaultState::from_bytes_mut(&mut data)?;
+
+        // Multisig check
+        state.check_signers(self.accounts.owner, self.accounts.remaining)?;
+
+        state.extend_lock(self.data.unlock_timestamp)?;
+        state.heartbeat(Clock::get()?.unix_timestamp);
+
+        Ok(())
+    }
+}
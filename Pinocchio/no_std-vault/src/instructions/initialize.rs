@@ -0,0 +1,120 @@
+use pinocchio::{
+    account::AccountView,
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{create_pda_account, find_state_address, is_unallocated, VaultError, VaultState};
+
+pub struct InitializeAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub state: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for InitializeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, state, system_program, _remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !owner.is_signer() {
+            return Err(VaultError::OwnerNotSigner.into());
+        }
+
+        // Program ID check (prevents arbitrary CPI)
+        if system_program.address() != &pinocchio_system::ID {
+            return Err(VaultError::InvalidSystemProgram.into());
+        }
+
+        // PDA check (state must belong to the signing owner)
+        let (state_address, bump) = find_state_address(owner.address());
+        if state.address() != &state_address {
+            return Err(VaultError::InvalidState.into());
+        }
+
+        // State must not exist yet (lamports alone don't count, anyone can
+        // pre-fund the PDA)
+        if !is_unallocated(state) {
+            return Err(VaultError::StateAlreadyInitialized.into());
+        }
+
+        Ok(Self { owner, state, system_program, bumps: [bump] })
+    }
+}
+
+pub struct InitializeData {
+    /// Unix timestamp before which withdrawals are rejected (0 for no lock)
+    pub unlock_timestamp: i64,
+}
+
+impl TryFrom<&[u8]> for InitializeData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != core::mem::size_of::<i64>() {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        let unlock_timestamp = i64::from_le_bytes(data.try_into().unwrap());
+
+        Ok(Self { unlock_timestamp })
+    }
+}
+
+pub struct Initialize<'a> {
+    pub accounts: InitializeAccounts<'a>,
+    pub data: InitializeData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Initialize<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = InitializeAccounts::try_from(accounts)?;
+        let data = InitializeData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> Initialize<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &3;
+
+    pub fn process(&self) -> ProgramResult {
+        let seeds = [
+            Seed::from(VaultState::SEED),
+            Seed::from(self.accounts.owner.address().as_ref()),
+            Seed::from(&self.accounts.bumps),
+        ];
+
+        let signers = [Signer::from(&seeds)];
+
+        // Create the program-owned state account at the PDA
+        create_pda_account(
+            self.accounts.owner,
+            self.accounts.state,
+            Rent::get()?.minimum_balance(VaultState::LEN),
+            VaultState::LEN as u64,
+            &crate::ID,
+            &signers,
+        )?;
+
+        let mut data = self.accounts.state.try_borrow_mut()?;
+        VaultState::from_uninit_bytes_mut(&mut data)?.init(
+            self.accounts.owner.address(),
+            self.accounts.bumps[0],
+            &Clock::get()?,
+            self.data.unlock_timestamp,
+        );
+
+        Ok(())
+    }
+}
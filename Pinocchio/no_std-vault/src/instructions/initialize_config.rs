@@ -0,0 +1,156 @@
+use pinocchio::{
+    account::AccountView,
+    address::Address,
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{create_pda_account, find_config_address, is_unallocated, Config, VaultError};
+
+/// Upgradeable BPF loader (`BPFLoaderUpgradeab1e11111111111111111111111`)
+pub const BPF_LOADER_UPGRADEABLE_ID: Address = Address::new_from_array([
+    0x02, 0xa8, 0xf6, 0x91, 0x4e, 0x88, 0xa1, 0xb0, 0xe2, 0x10, 0x15, 0x3e, 0xf7, 0x63, 0xae, 0x2b,
+    0x00, 0xc2, 0xb9, 0x3d, 0x16, 0xc1, 0x24, 0xd2, 0xc0, 0x53, 0x7a, 0x10, 0x04, 0x80, 0x00, 0x00,
+]);
+
+/// `ProgramData` layout: `[tag (u32 = 3), slot (u64), authority option (u8),
+/// authority (32 bytes)]`
+const PROGRAM_DATA_TAG: [u8; 4] = 3u32.to_le_bytes();
+const PROGRAM_DATA_AUTHORITY_OFFSET: usize = 13;
+
+/// `admin` signs and pays; it must be the program's upgrade authority so no
+/// one can front-run the deployment and take over the config
+pub struct InitializeConfigAccounts<'a> {
+    pub admin: &'a AccountView,
+    pub config: &'a AccountView,
+    pub program_data: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for InitializeConfigAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [admin, config, program_data, system_program, _remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !admin.is_signer() {
+            return Err(VaultError::InvalidAdmin.into());
+        }
+
+        // Program ID check (prevents arbitrary CPI)
+        if system_program.address() != &pinocchio_system::ID {
+            return Err(VaultError::InvalidSystemProgram.into());
+        }
+
+        // PDA check (config is a single global account)
+        let (config_address, bump) = find_config_address();
+        if config.address() != &config_address {
+            return Err(VaultError::InvalidConfig.into());
+        }
+
+        // Config must not exist yet; lamports sent to the address beforehand
+        // do not count
+        if !is_unallocated(config) {
+            return Err(VaultError::InvalidConfig.into());
+        }
+
+        // Upgrade authority check
+        let (program_data_address, _) =
+            Address::find_program_address(&[crate::ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID);
+        if program_data.address() != &program_data_address
+            || !program_data.owned_by(&BPF_LOADER_UPGRADEABLE_ID)
+        {
+            return Err(VaultError::InvalidAdmin.into());
+        }
+
+        {
+            let data = program_data.try_borrow()?;
+            let authority = data
+                .get(PROGRAM_DATA_AUTHORITY_OFFSET..PROGRAM_DATA_AUTHORITY_OFFSET + 32)
+                .ok_or(VaultError::InvalidAdmin)?;
+
+            if data[..4] != PROGRAM_DATA_TAG
+                || data[PROGRAM_DATA_AUTHORITY_OFFSET - 1] != 1
+                || authority != admin.address().as_ref()
+            {
+                return Err(VaultError::InvalidAdmin.into());
+            }
+        }
+
+        Ok(Self { admin, config, program_data, system_program, bumps: [bump] })
+    }
+}
+
+/// `[treasury (32 bytes), fee_bps (u16)]`
+pub struct InitializeConfigData {
+    pub treasury: Address,
+    pub fee_bps: u16,
+}
+
+impl TryFrom<&[u8]> for InitializeConfigData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != 32 + core::mem::size_of::<u16>() {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        let treasury = Address::new_from_array(data[..32].try_into().unwrap());
+        let fee_bps = u16::from_le_bytes(data[32..].try_into().unwrap());
+
+        Ok(Self { treasury, fee_bps })
+    }
+}
+
+/// Creates the global config with the signer as admin
+pub struct InitializeConfig<'a> {
+    pub accounts: InitializeConfigAccounts<'a>,
+    pub data: InitializeConfigData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for InitializeConfig<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = InitializeConfigAccounts::try_from(accounts)?;
+        let data = InitializeConfigData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> InitializeConfig<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &21;
+
+    pub fn process(&self) -> ProgramResult {
+        let seeds = [Seed::from(Config::SEED), Seed::from(&self.accounts.bumps)];
+
+        let signers = [Signer::from(&seeds)];
+
+        // Create the program-owned config account at the PDA
+        create_pda_account(
+            self.accounts.admin,
+            self.accounts.config,
+            Rent::get()?.minimum_balance(Config::LEN),
+            Config::LEN as u64,
+            &crate::ID,
+            &signers,
+        )?;
+
+        let mut data = self.accounts.config.try_borrow_mut()?;
+
+        Config::from_bytes_mut(&mut data)?.init(
+            self.accounts.admin.address(),
+            &self.data.treasury,
+            self.data.fee_bps,
+            self.accounts.bumps[0],
+        )
+    }
+}
@@ -0,0 +1,53 @@
+pub mod deposit;
+pub mod withdraw;
+pub mod partial_withdraw;
+pub mod initialize;
+pub mod extend_lock;
+pub mod deposit_token;
+pub mod withdraw_token;
+pub mod configure_multisig;
+pub mod set_beneficiary;
+pub mod claim;
+pub mod deposit_for;
+pub mod set_hook;
+pub mod withdraw_to;
+pub mod withdraw_signed;
+pub mod add_allowlist_entry;
+pub mod remove_allowlist_entry;
+pub mod set_withdraw_cap;
+pub mod create_stream;
+pub mod claim_stream;
+pub mod cancel_stream;
+pub mod close;
+pub mod initialize_config;
+pub mod update_config;
+pub mod delegate_stake;
+pub mod deactivate_stake;
+pub mod withdraw_stake;
+
+pub use deposit::*;
+pub use withdraw::*;
+pub use partial_withdraw::*;
+pub use initialize::*;
+pub use extend_lock::*;
+pub use deposit_token::*;
+pub use withdraw_token::*;
+pub use configure_multisig::*;
+pub use set_beneficiary::*;
+pub use claim::*;
+pub use deposit_for::*;
+pub use set_hook::*;
+pub use withdraw_to::*;
+pub use withdraw_signed::*;
+pub use add_allowlist_entry::*;
+pub use remove_allowlist_entry::*;
+pub use set_withdraw_cap::*;
+pub use create_stream::*;
+pub use claim_stream::*;
+pub use cancel_stream::*;
+pub use close::*;
+pub use initialize_config::*;
+pub use update_config::*;
+pub use delegate_stake::*;
+pub use deactivate_stake::*;
+pub use withdraw_stake::*;
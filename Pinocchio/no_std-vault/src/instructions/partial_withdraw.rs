@@ -0,0 +1,52 @@
+use pinocchio::{account::AccountView, error::ProgramError, ProgramResult};
+
+use crate::{VaultError, WithdrawAccounts};
+
+pub struct PartialWithdrawData {
+    pub amount: u64,
+}
+
+impl TryFrom<&[u8]> for PartialWithdrawData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != core::mem::size_of::<u64>() {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+        // Validate amount is not zero
+        if amount == 0 {
+            return Err(VaultError::ZeroAmount.into());
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+pub struct PartialWithdraw<'a> {
+    pub accounts: WithdrawAccounts<'a>,
+    pub data: PartialWithdrawData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for PartialWithdraw<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = WithdrawAccounts::try_from(accounts)?;
+        let data = PartialWithdrawData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> PartialWithdraw<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &2;
+
+    pub fn process(&self) -> ProgramResult {
+        // Transfer only the requested lamports from vault to owner
+        self.accounts.withdraw(self.accounts.owner, self.data.amount)
+    }
+}
@@ -0,0 +1,62 @@
+use pinocchio::{
+    account::AccountView,
+    address::Address,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{AllowlistAccounts, VaultError, VaultState};
+
+/// `[destination (32 bytes)]`
+pub struct RemoveAllowlistEntryData {
+    pub destination: Address,
+}
+
+impl TryFrom<&[u8]> for RemoveAllowlistEntryData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        let destination: [u8; 32] = data
+            .try_into()
+            .map_err(|_| ProgramError::from(VaultError::InvalidInstructionData))?;
+
+        Ok(Self { destination: Address::new_from_array(destination) })
+    }
+}
+
+/// Removes a withdrawal destination immediately. A vault never leaves
+/// allowlist mode, so the last entry has to be replaced rather than removed.
+pub struct RemoveAllowlistEntry<'a> {
+    pub accounts: AllowlistAccounts<'a>,
+    pub data: RemoveAllowlistEntryData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for RemoveAllowlistEntry<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = AllowlistAccounts::try_from(accounts)?;
+        let data = RemoveAllowlistEntryData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> RemoveAllowlistEntry<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &15;
+
+    pub fn process(&self) -> ProgramResult {
+        let mut data = self.accounts.state.try_borrow_mut()?;
+        let state = VaultState::from_bytes_mut(&mut data)?;
+
+        // Vault configuration changes need the multisig threshold
+        state.check_signers(self.accounts.owner, self.accounts.remaining)?;
+
+        state.remove_allowlist_entry(&self.data.destination)?;
+        state.heartbeat(Clock::get()?.unix_timestamp);
+
+        Ok(())
+    }
+}
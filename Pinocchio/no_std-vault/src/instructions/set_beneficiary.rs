@@ -0,0 +1,94 @@
+use pinocchio::{
+    account::AccountView,
+    address::Address,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{VaultError, VaultState};
+
+pub struct SetBeneficiaryAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub state: &'a AccountView,
+    pub remaining: &'a [AccountView],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for SetBeneficiaryAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, state, remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !owner.is_signer() {
+            return Err(VaultError::OwnerNotSigner.into());
+        }
+
+        // State check (vault must be initialized)
+        VaultState::check(state, owner.address())?;
+
+        Ok(Self { owner, state, remaining })
+    }
+}
+
+/// `[beneficiary (32 bytes), inactivity_period (i64 seconds)]`; an all-zero
+/// beneficiary removes the current one
+pub struct SetBeneficiaryData {
+    pub beneficiary: Option<Address>,
+    pub inactivity_period: i64,
+}
+
+impl TryFrom<&[u8]> for SetBeneficiaryData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != 32 + core::mem::size_of::<i64>() {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        let (beneficiary, inactivity_period) = data.split_at(32);
+
+        let beneficiary: [u8; 32] = beneficiary.try_into().unwrap();
+        let beneficiary = (beneficiary != [0; 32]).then(|| Address::new_from_array(beneficiary));
+        let inactivity_period = i64::from_le_bytes(inactivity_period.try_into().unwrap());
+
+        Ok(Self { beneficiary, inactivity_period })
+    }
+}
+
+pub struct SetBeneficiary<'a> {
+    pub accounts: SetBeneficiaryAccounts<'a>,
+    pub data: SetBeneficiaryData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for SetBeneficiary<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = SetBeneficiaryAccounts::try_from(accounts)?;
+        let data = SetBeneficiaryData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> SetBeneficiary<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &8;
+
+    pub fn process(&self) -> ProgramResult {
+        let mut data = self.accounts.state.try_borrow_mut()?;
+        let state = VaultState::from_bytes_mut(&mut data)?;
+
+        // Redirecting recovery funds needs the same approval as a withdrawal
+        state.check_signers(self.accounts.owner, self.accounts.remaining)?;
+
+        state.set_beneficiary(self.data.beneficiary.as_ref(), self.data.inactivity_period)?;
+        state.heartbeat(Clock::get()?.unix_timestamp);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use pinocchio::{
+    account::AccountView,
+    address::Address,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{VaultError, VaultState};
+
+pub struct SetHookAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub state: &'a AccountView,
+    pub remaining: &'a [AccountView],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for SetHookAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, state, remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !owner.is_signer() {
+            return Err(VaultError::OwnerNotSigner.into());
+        }
+
+        // State check (vault must be initialized)
+        VaultState::check(state, owner.address())?;
+
+        Ok(Self { owner, state, remaining })
+    }
+}
+
+/// `[hook_program (32 bytes)]`; an all-zero address removes the hook
+pub struct SetHookData {
+    pub hook: Option<Address>,
+}
+
+impl TryFrom<&[u8]> for SetHookData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        let hook: [u8; 32] = data
+            .try_into()
+            .map_err(|_| ProgramError::from(VaultError::InvalidInstructionData))?;
+
+        let hook = (hook != [0; 32]).then(|| Address::new_from_array(hook));
+
+        Ok(Self { hook })
+    }
+}
+
+pub struct SetHook<'a> {
+    pub accounts: SetHookAccounts<'a>,
+    pub data: SetHookData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for SetHook<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = SetHookAccounts::try_from(accounts)?;
+        let data = SetHookData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> SetHook<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &11;
+
+    pub fn process(&self) -> ProgramResult {
+        let mut data = self.accounts.state.try_borrow_mut()?;
+        let state = VaultState::from_bytes_mut(&mut data)?;
+
+        // Vault configuration changes need the multisig threshold
+        state.check_signers(self.accounts.owner, self.accounts.remaining)?;
+
+        state.set_hook(self.data.hook.as_ref())?;
+        state.heartbeat(Clock::get()?.unix_timestamp);
+
+        Ok(())
+    }
+}
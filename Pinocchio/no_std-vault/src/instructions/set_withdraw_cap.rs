@@ -0,0 +1,88 @@
+use pinocchio::{
+    account::AccountView,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{VaultError, VaultState};
+
+pub struct SetWithdrawCapAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub state: &'a AccountView,
+    pub remaining: &'a [AccountView],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for SetWithdrawCapAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [owner, state, remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !owner.is_signer() {
+            return Err(VaultError::OwnerNotSigner.into());
+        }
+
+        // State check (vault must be initialized)
+        VaultState::check(state, owner.address())?;
+
+        Ok(Self { owner, state, remaining })
+    }
+}
+
+/// `[cap (u64 lamports per epoch)]`; zero removes the cap
+pub struct SetWithdrawCapData {
+    pub cap: u64,
+}
+
+impl TryFrom<&[u8]> for SetWithdrawCapData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != core::mem::size_of::<u64>() {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        Ok(Self { cap: u64::from_le_bytes(data.try_into().unwrap()) })
+    }
+}
+
+/// Caps the lamports withdrawable per epoch across every lamport withdraw
+/// path; beneficiary claims and token withdrawals are not counted
+pub struct SetWithdrawCap<'a> {
+    pub accounts: SetWithdrawCapAccounts<'a>,
+    pub data: SetWithdrawCapData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for SetWithdrawCap<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = SetWithdrawCapAccounts::try_from(accounts)?;
+        let data = SetWithdrawCapData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> SetWithdrawCap<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &16;
+
+    pub fn process(&self) -> ProgramResult {
+        let now = Clock::get()?.unix_timestamp;
+        let mut data = self.accounts.state.try_borrow_mut()?;
+        let state = VaultState::from_bytes_mut(&mut data)?;
+
+        // Vault configuration changes need the multisig threshold
+        state.check_signers(self.accounts.owner, self.accounts.remaining)?;
+
+        state.set_withdraw_cap(self.data.cap, now)?;
+        state.heartbeat(now);
+
+        Ok(())
+    }
+}
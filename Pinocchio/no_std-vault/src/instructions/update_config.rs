@@ -0,0 +1,99 @@
+use pinocchio::{
+    account::AccountView,
+    address::Address,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{find_config_address, Config, VaultError};
+
+pub struct UpdateConfigAccounts<'a> {
+    pub admin: &'a AccountView,
+    pub config: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for UpdateConfigAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [admin, config, _remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !admin.is_signer() {
+            return Err(VaultError::InvalidAdmin.into());
+        }
+
+        // PDA check (config is a single global account)
+        if config.address() != &find_config_address().0 || !config.owned_by(&crate::ID) {
+            return Err(VaultError::InvalidConfig.into());
+        }
+
+        // Admin check
+        if &Config::from_bytes(&config.try_borrow()?)?.admin != admin.address() {
+            return Err(VaultError::InvalidAdmin.into());
+        }
+
+        Ok(Self { admin, config })
+    }
+}
+
+/// `[admin (32 bytes), treasury (32 bytes), fee_bps (u16)]`
+pub struct UpdateConfigData {
+    pub admin: Address,
+    pub treasury: Address,
+    pub fee_bps: u16,
+}
+
+impl TryFrom<&[u8]> for UpdateConfigData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != 64 + core::mem::size_of::<u16>() {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        let admin = Address::new_from_array(data[..32].try_into().unwrap());
+        let treasury = Address::new_from_array(data[32..64].try_into().unwrap());
+        let fee_bps = u16::from_le_bytes(data[64..].try_into().unwrap());
+
+        Ok(Self { admin, treasury, fee_bps })
+    }
+}
+
+/// Replaces the admin, treasury and fee; only the current admin can call it.
+/// A higher fee only applies after [`FEE_CHANGE_DELAY`](crate::FEE_CHANGE_DELAY).
+pub struct UpdateConfig<'a> {
+    pub accounts: UpdateConfigAccounts<'a>,
+    pub data: UpdateConfigData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for UpdateConfig<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = UpdateConfigAccounts::try_from(accounts)?;
+        let data = UpdateConfigData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> UpdateConfig<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &22;
+
+    pub fn process(&self) -> ProgramResult {
+        let now = Clock::get()?.unix_timestamp;
+        let mut data = self.accounts.config.try_borrow_mut()?;
+
+        Config::from_bytes_mut(&mut data)?.set(
+            &self.data.admin,
+            &self.data.treasury,
+            self.data.fee_bps,
+            now,
+        )
+    }
+}
@@ -0,0 +1,175 @@
+use pinocchio::{
+    account::AccountView,
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use pinocchio_system::instructions::Transfer;
+
+use crate::{find_vault_address, VaultError, VaultEvent, VaultEventKind, VaultState};
+
+pub struct WithdrawAccounts<'a> {
+    pub owner: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub state: &'a AccountView,
+    pub remaining: &'a [AccountView],
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for WithdrawAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+
+        let [owner, vault, system_program, state, remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        // Signer check with proper error type
+        if !owner.is_signer() {
+            return Err(VaultError::OwnerNotSigner.into());
+        }
+
+        Self::new(owner, vault, system_program, state, remaining)
+    }
+}
+
+impl<'a> WithdrawAccounts<'a> {
+    /// Parses the same layout without requiring the owner to sign; the caller
+    /// must authorize the withdrawal some other way, as
+    /// [`WithdrawSigned`](crate::WithdrawSigned) does
+    pub fn try_from_relayed(accounts: &'a [AccountView]) -> Result<Self, ProgramError> {
+        let [owner, vault, system_program, state, remaining @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        Self::new(owner, vault, system_program, state, remaining)
+    }
+
+    fn new(
+        owner: &'a AccountView,
+        vault: &'a AccountView,
+        system_program: &'a AccountView,
+        state: &'a AccountView,
+        remaining: &'a [AccountView],
+    ) -> Result<Self, ProgramError> {
+        // Owner check
+        if !vault.owned_by(&pinocchio_system::ID) {
+            return Err(VaultError::InvalidVaultOwner.into());
+        }
+
+        // Program ID check (prevents arbitrary CPI)
+        if system_program.address() != &pinocchio_system::ID {
+            return Err(VaultError::InvalidSystemProgram.into());
+        }
+
+        // PDA check (vault must belong to the owner)
+        let (vault_address, bump) = find_vault_address(owner.address());
+        if vault.address() != &vault_address {
+            return Err(VaultError::InvalidVault.into());
+        }
+
+        // Check vault has lamports to withdraw
+        if vault.lamports() == 0 {
+            return Err(VaultError::VaultEmpty.into());
+        }
+
+        // State check (vault must be initialized)
+        VaultState::check(state, owner.address())?;
+
+        Ok(Self { owner, vault, system_program, state, remaining, bumps: [bump] })
+    }
+
+    /// Moves `amount` lamports from the vault to `recipient` once the time
+    /// lock, multisig, allowlist and rate limit checks pass, then records and
+    /// logs the withdrawal
+    pub fn withdraw(&self, recipient: &AccountView, amount: u64) -> ProgramResult {
+        let balance = self.vault.lamports();
+
+        let clock = Clock::get()?;
+        let mut data = self.state.try_borrow_mut()?;
+        let state = VaultState::from_bytes_mut(&mut data)?;
+
+        // Time lock check
+        state.check_unlocked(clock.unix_timestamp)?;
+
+        // Multisig check
+        state.check_signers(self.owner, self.remaining)?;
+
+        // Allowlist check
+        state.check_allowlisted(recipient.address(), clock.unix_timestamp)?;
+
+        // Rate limit check
+        state.consume_withdraw_limit(amount, &clock)?;
+
+        // Cannot withdraw more than the vault holds
+        let remaining = balance
+            .checked_sub(amount)
+            .ok_or(VaultError::AmountExceedsBalance)?;
+
+        // Whatever stays behind must keep the vault rent exempt so it can
+        // keep receiving deposits; use `Withdraw` to drain it completely
+        if remaining != 0 && remaining < Rent::get()?.minimum_balance(0) {
+            return Err(VaultError::BelowRentExempt.into());
+        }
+
+        // Create PDA signer seeds
+        let seeds = [
+            Seed::from(b"vault"),
+            Seed::from(self.owner.address().as_ref()),
+            Seed::from(&self.bumps),
+        ];
+
+        let signers = [Signer::from(&seeds)];
+
+        Transfer {
+            from: self.vault,
+            to: recipient,
+            lamports: amount,
+        }
+        .invoke_signed(&signers)?;
+
+        // Record the withdrawal in the vault history
+        state.record_withdrawal(amount, clock.slot)?;
+        state.heartbeat(clock.unix_timestamp);
+
+        VaultEvent {
+            kind: VaultEventKind::Withdraw,
+            owner: *self.owner.address(),
+            vault: *self.vault.address(),
+            amount,
+            balance: self.vault.lamports(),
+        }
+        .emit();
+
+        Ok(())
+    }
+}
+
+
+pub struct Withdraw<'a> {
+    pub accounts: WithdrawAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Withdraw<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = WithdrawAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Withdraw<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &1;
+
+    pub fn process(&self) -> ProgramResult {
+        // Transfer all lamports from vault to owner
+        self.accounts.withdraw(self.accounts.owner, self.accounts.vault.lamports())
+    }
+}
@@ -0,0 +1,123 @@
+use pinocchio::{
+    account::AccountView,
+    address::Address,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{check_ed25519_signature, VaultError, VaultState, WithdrawAccounts};
+
+/// Gasless withdrawal: a relayer submits it and pays the fees, while the owner
+/// authorizes it off-chain with an Ed25519 signature over
+/// [`WithdrawSignedData::message`]. The Ed25519 program instruction carrying
+/// that signature must come right before this one.
+///
+/// Accounts are `[recipient, instructions_sysvar, owner, vault, system_program,
+/// state, signers..]`. Multisig vaults still need their co-signers to sign
+/// the transaction itself.
+pub struct WithdrawSigned<'a> {
+    pub recipient: &'a AccountView,
+    pub instructions: &'a AccountView,
+    pub accounts: WithdrawAccounts<'a>,
+    pub data: WithdrawSignedData,
+}
+
+/// `[amount (u64), nonce (u64), expiry (i64)]`
+pub struct WithdrawSignedData {
+    pub amount: u64,
+    pub nonce: u64,
+    pub expiry: i64,
+}
+
+impl TryFrom<&[u8]> for WithdrawSignedData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(VaultError::InvalidInstructionData.into());
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let nonce = u64::from_le_bytes(data[8..16].try_into().unwrap());
+        let expiry = i64::from_le_bytes(data[16..24].try_into().unwrap());
+
+        // Validate amount is not zero
+        if amount == 0 {
+            return Err(VaultError::ZeroAmount.into());
+        }
+
+        Ok(Self { amount, nonce, expiry })
+    }
+}
+
+impl WithdrawSignedData {
+    pub const LEN: usize = 24;
+    pub const MESSAGE_LEN: usize = 32 + 32 + 8 + Self::LEN;
+
+    /// Bytes the owner signs: `[vault, recipient, init_slot, amount, nonce,
+    /// expiry]`, where `init_slot` is the vault's
+    /// [`VaultState::init_slot`]
+    pub fn message(&self, vault: &Address, recipient: &Address, init_slot: u64) -> [u8; Self::MESSAGE_LEN] {
+        let mut message = [0u8; Self::MESSAGE_LEN];
+
+        message[0..32].copy_from_slice(vault.as_ref());
+        message[32..64].copy_from_slice(recipient.as_ref());
+        message[64..72].copy_from_slice(&init_slot.to_le_bytes());
+        message[72..80].copy_from_slice(&self.amount.to_le_bytes());
+        message[80..88].copy_from_slice(&self.nonce.to_le_bytes());
+        message[88..96].copy_from_slice(&self.expiry.to_le_bytes());
+
+        message
+    }
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for WithdrawSigned<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let [recipient, instructions, accounts @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        let accounts = WithdrawAccounts::try_from_relayed(accounts)?;
+        let data = WithdrawSignedData::try_from(data)?;
+
+        // Recipient check (must be able to receive lamports)
+        if !recipient.is_writable() || recipient.address() == accounts.vault.address() {
+            return Err(VaultError::InvalidRecipient.into());
+        }
+
+        Ok(Self { recipient, instructions, accounts, data })
+    }
+}
+
+impl<'a> WithdrawSigned<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &13;
+
+    pub fn process(&self) -> ProgramResult {
+        // Expiry check
+        if Clock::get()?.unix_timestamp > self.data.expiry {
+            return Err(VaultError::AuthorizationExpired.into());
+        }
+
+        {
+            let mut data = self.accounts.state.try_borrow_mut()?;
+            let state = VaultState::from_bytes_mut(&mut data)?;
+
+            // Signature check (stands in for the owner signer check)
+            let message = self.data.message(
+                self.accounts.vault.address(),
+                self.recipient.address(),
+                state.init_slot(),
+            );
+            check_ed25519_signature(self.instructions, self.accounts.owner.address(), &message)?;
+
+            // Replay check
+            state.use_nonce(self.data.nonce)?;
+        }
+
+        self.accounts.withdraw(self.recipient, self.data.amount)
+    }
+}
@@ -0,0 +1,58 @@
+use pinocchio::{
+    account::AccountView,
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{withdraw_stake, StakeAccounts, VaultError};
+
+/// Returns the whole stake account balance, rewards included, to the vault
+/// and closes the stake account. Accounts are `[stake_history, owner, vault,
+/// state, stake, stake_program, clock, signers..]`; the stake must be fully
+/// deactivated first.
+pub struct WithdrawStake<'a> {
+    pub stake_history: &'a AccountView,
+    pub accounts: StakeAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for WithdrawStake<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [stake_history, accounts @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        let accounts = StakeAccounts::try_from(accounts)?;
+
+        Ok(Self { stake_history, accounts })
+    }
+}
+
+impl<'a> WithdrawStake<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &25;
+
+    pub fn process(&self) -> ProgramResult {
+        self.accounts.authorize()?;
+
+        let seeds = [
+            Seed::from(b"vault"),
+            Seed::from(self.accounts.owner.address().as_ref()),
+            Seed::from(&self.accounts.bumps),
+        ];
+
+        let signers = [Signer::from(&seeds)];
+
+        withdraw_stake(
+            self.accounts.stake,
+            self.accounts.vault,
+            self.accounts.clock,
+            self.stake_history,
+            self.accounts.vault,
+            self.accounts.stake.lamports(),
+            &signers,
+        )
+    }
+}
@@ -0,0 +1,42 @@
+use pinocchio::{account::AccountView, error::ProgramError, ProgramResult};
+
+use crate::{PartialWithdrawData, VaultError, WithdrawAccounts};
+
+/// Withdrawal paid straight to a third party, e.g. a merchant or an exchange
+/// deposit address. Accounts are `[recipient, owner, vault, system_program,
+/// state, signers..]`; the owner still signs.
+pub struct WithdrawTo<'a> {
+    pub recipient: &'a AccountView,
+    pub accounts: WithdrawAccounts<'a>,
+    pub data: PartialWithdrawData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for WithdrawTo<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let [recipient, accounts @ ..] = accounts else {
+            return Err(VaultError::NotEnoughAccounts.into());
+        };
+
+        let accounts = WithdrawAccounts::try_from(accounts)?;
+        let data = PartialWithdrawData::try_from(data)?;
+
+        // Recipient check (must be able to receive lamports)
+        if !recipient.is_writable() || recipient.address() == accounts.vault.address() {
+            return Err(VaultError::InvalidRecipient.into());
+        }
+
+        Ok(Self { recipient, accounts, data })
+    }
+}
+
+impl<'a> WithdrawTo<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &12;
+
+    pub fn process(&self) -> ProgramResult {
+        // Transfer the requested lamports from vault to recipient
+        self.accounts.withdraw(self.recipient, self.data.amount)
+    }
+}
@@ -0,0 +1,70 @@
+use pinocchio::{
+    account::AccountView,
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{transfer_checked, PartialWithdrawData, TokenVaultAccounts, VaultState};
+
+pub struct WithdrawToken<'a> {
+    pub accounts: TokenVaultAccounts<'a>,
+    pub data: PartialWithdrawData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for WithdrawToken<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = TokenVaultAccounts::try_from(accounts)?;
+        let data = PartialWithdrawData::try_from(data)?;
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> WithdrawToken<'a> {
+
+    pub const DISCRIMINATOR: &'a u8 = &6;
+
+    pub fn process(&self) -> ProgramResult {
+        let clock = Clock::get()?;
+        let mut data = self.accounts.state.try_borrow_mut()?;
+        let state = VaultState::from_bytes_mut(&mut data)?;
+
+        // Time lock check
+        state.check_unlocked(clock.unix_timestamp)?;
+
+        // Multisig check
+        state.check_signers(self.accounts.owner, self.accounts.remaining)?;
+
+        // Allowlist check (the destination token account belongs to the owner)
+        state.check_allowlisted(self.accounts.owner.address(), clock.unix_timestamp)?;
+
+        // Vault PDA is the token account authority
+        let seeds = [
+            Seed::from(b"vault"),
+            Seed::from(self.accounts.owner.address().as_ref()),
+            Seed::from(&self.accounts.bumps),
+        ];
+
+        let signers = [Signer::from(&seeds)];
+
+        transfer_checked(
+            self.accounts.token_program,
+            self.accounts.vault_token_account,
+            self.accounts.mint,
+            self.accounts.owner_token_account,
+            self.accounts.vault,
+            self.data.amount,
+            self.accounts.decimals,
+            &signers,
+        )?;
+
+        state.set_last_activity_slot(clock.slot);
+        state.heartbeat(clock.unix_timestamp);
+
+        Ok(())
+    }
+}
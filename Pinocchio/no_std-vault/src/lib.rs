@@ -1,19 +1,42 @@
 #![no_std]
 
-use pinocchio::{
-    account::AccountView,
-    address::Address,
-    entrypoint,
-    nostd_panic_handler,
-    error::ProgramError,
-    ProgramResult,
-    cpi::{Seed, Signer},
-};
-
-use pinocchio_system::instructions::Transfer;
-
-entrypoint!(process_instruction);
-nostd_panic_handler!();
+use pinocchio::{account::AccountView, address::Address, ProgramResult};
+
+mod errors;
+mod state;
+mod token;
+mod hook;
+mod events;
+mod envelope;
+mod ed25519;
+mod stake;
+mod pda;
+pub mod instructions;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "cpi")]
+pub mod cpi;
+
+#[cfg(test)]
+mod tests;
+#[cfg(test)]
+mod fuzz;
+
+pub use errors::*;
+pub use state::*;
+pub use token::*;
+pub use hook::*;
+pub use events::*;
+pub use envelope::*;
+pub use ed25519::*;
+pub use stake::*;
+pub use pda::*;
+pub use instructions::*;
+
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::nostd_panic_handler!();
 
 // Program ID using Address::new_from_array as per guidelines
 pub const ID: Address = Address::new_from_array([
@@ -21,191 +44,76 @@ pub const ID: Address = Address::new_from_array([
     0x19, 0x92, 0xba, 0xe8, 0xaf, 0xd1, 0xcd, 0x07, 0x8e, 0xf8, 0xaf, 0x70, 0x47, 0xdc, 0x11, 0xf7,
 ]);
 
-// Updated function signature using new types
-fn process_instruction(
-    _program_id: &Address,
-    accounts: &[AccountView],
-    instruction_data: &[u8],
-) -> ProgramResult {
-    match instruction_data.split_first() {
-        Some((0, data)) => Deposit::try_from((data, accounts))?.process(),
-        Some((1, _)) => Withdraw::try_from(accounts)?.process(),
-        _ => Err(ProgramError::InvalidInstructionData),
-    }
+/// Derives the `["vault", owner]` PDA holding the owner's lamports
+pub fn find_vault_address(owner: &Address) -> (Address, u8) {
+    Address::find_program_address(&[b"vault", owner.as_ref()], &ID)
 }
 
-pub struct DepositAccounts<'a> {
-    pub owner: &'a AccountView,
-    pub vault: &'a AccountView,
-    pub system_program: &'a AccountView,
+/// Derives the `["state", owner]` PDA holding the owner's [`VaultState`]
+pub fn find_state_address(owner: &Address) -> (Address, u8) {
+    Address::find_program_address(&[VaultState::SEED, owner.as_ref()], &ID)
 }
 
-impl<'a> TryFrom<&'a [AccountView]> for DepositAccounts<'a> {
-    type Error = ProgramError;
-
-    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
-        let [owner, vault, system_program, _remaining @ ..] = accounts else {
-            return Err(ProgramError::NotEnoughAccountKeys);
-        };
-
-        // Signer check with proper error type
-        if !owner.is_signer() {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-
-        // Owner check using proper method name
-        if !vault.owned_by(&pinocchio_system::ID) {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-
-        // Program ID check (prevents arbitrary CPI)
-        if system_program.address() != &pinocchio_system::ID {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-
-        Ok(Self { owner, vault, system_program })
-    }
+/// Derives the global `["config"]` PDA holding the protocol [`Config`]
+pub fn find_config_address() -> (Address, u8) {
+    Address::find_program_address(&[Config::SEED], &ID)
 }
 
-
-
-pub struct DepositData {
-    pub amount: u64,
+/// Derives the `["stake", owner]` PDA of the stake account holding the
+/// vault's delegated lamports
+pub fn find_stake_address(owner: &Address) -> (Address, u8) {
+    Address::find_program_address(&[STAKE_SEED, owner.as_ref()], &ID)
 }
 
-impl TryFrom<&[u8]> for DepositData {
-    type Error = ProgramError;
-
-    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
-        if data.len() != core::mem::size_of::<u64>() {
-            return Err(ProgramError::InvalidInstructionData);
-        }
-
-        let amount = u64::from_le_bytes(data.try_into().unwrap());
-
-        // Validate amount is not zero
-        if amount == 0 {
-            return Err(ProgramError::InvalidInstructionData);
-        }
-
-        Ok(Self { amount })
-    }
-}
-
-pub struct Deposit<'a> {
-    pub accounts: DepositAccounts<'a>,
-    pub data: DepositData,
-}
-
-impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Deposit<'a> {
-    type Error = ProgramError;
-
-    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
-        let accounts = DepositAccounts::try_from(accounts)?;
-        let data = DepositData::try_from(data)?;
-
-        Ok(Self { accounts, data })
-    }
+/// Derives the `["stream", owner, recipient]` PDA holding the [`Stream`] schedule
+pub fn find_stream_address(owner: &Address, recipient: &Address) -> (Address, u8) {
+    Address::find_program_address(&[Stream::SEED, owner.as_ref(), recipient.as_ref()], &ID)
 }
 
-impl<'a> Deposit<'a> {
-
-    pub const DISCRIMINATOR: &'a u8 = &0;
-    pub fn process(&self) -> ProgramResult {
-        Transfer {
-            from: self.accounts.owner,
-            to: self.accounts.vault,
-            lamports: self.data.amount,
-        }
-        .invoke()?;
-
-        Ok(())
-    }
+/// Derives the `["stream_escrow", stream]` PDA holding a stream's lamports
+pub fn find_stream_escrow_address(stream: &Address) -> (Address, u8) {
+    Address::find_program_address(&[Stream::ESCROW_SEED, stream.as_ref()], &ID)
 }
 
-
-pub struct WithdrawAccounts<'a> {
-    pub owner: &'a AccountView,
-    pub vault: &'a AccountView,
-    pub system_program: &'a AccountView,
-    pub bumps: [u8; 1],
-}
-
-impl<'a> TryFrom<&'a [AccountView]> for WithdrawAccounts<'a> {
-    type Error = ProgramError;
-
-    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
-
-        let [owner, vault, system_program, _remaining@ ..] = accounts else {
-            return Err(ProgramError::NotEnoughAccountKeys);
-        };
-
-
-        // Signer check with proper error type
-        if !owner.is_signer() {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-
-        // Owner check
-        if !vault.owned_by(&pinocchio_system::ID) {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-
-        // Program ID check (prevents arbitrary CPI)
-        if system_program.address() != &pinocchio_system::ID {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-
-        // Check vault has lamports to withdraw
-        if vault.lamports() == 0 {
-            return Err(ProgramError::InvalidAccountData);
-        }
-
-        let bump = 255u8; // You'll need to handle this properly in production
-
-        Ok(Self { owner, vault, system_program, bumps: [bump] })
-       
-    }
-}
-
-
-pub struct Withdraw<'a> {
-    pub accounts: WithdrawAccounts<'a>,
-}
-
-impl<'a> TryFrom<&'a [AccountView]> for Withdraw<'a> {
-    type Error = ProgramError;
-
-    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
-        let accounts = WithdrawAccounts::try_from(accounts)?;
-
-        Ok(Self { accounts })
-    }
-}
-
-impl<'a> Withdraw<'a> {
-
-    pub const DISCRIMINATOR: &'a u8 = &1;
-
-    pub fn process(&self) -> ProgramResult {
-
-        // Create PDA signer seeds
-        let seeds = [
-            Seed::from(b"vault"),
-            Seed::from(self.accounts.owner.address().as_ref()),
-            Seed::from(&self.accounts.bumps),
-        ];
-
-        let signers = [Signer::from(&seeds)];
-
-        // Transfer all lamports from vault to owner
-        Transfer {
-            from: self.accounts.vault,
-            to: self.accounts.owner,
-            lamports: self.accounts.vault.lamports(),
-        }
-        .invoke_signed(&signers)?;
-
-        Ok(())
+// Updated function signature using new types
+pub fn process_instruction(
+    _program_id: &Address,
+    accounts: &[AccountView],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let Envelope { discriminator, version, payload } = Envelope::try_from(instruction_data)?;
+
+    // Each (instruction, version) pair has its own parser
+    match (discriminator, version) {
+        (0, LEGACY_VERSION) => Deposit::try_from((payload, accounts))?.process(),
+        (0, 1) => Deposit::try_from_v1((payload, accounts))?.process(),
+        (1, LEGACY_VERSION) => Withdraw::try_from(accounts)?.process(),
+        (2, LEGACY_VERSION) => PartialWithdraw::try_from((payload, accounts))?.process(),
+        (3, LEGACY_VERSION) => Initialize::try_from((payload, accounts))?.process(),
+        (4, LEGACY_VERSION) => ExtendLock::try_from((payload, accounts))?.process(),
+        (5, LEGACY_VERSION) => DepositToken::try_from((payload, accounts))?.process(),
+        (6, LEGACY_VERSION) => WithdrawToken::try_from((payload, accounts))?.process(),
+        (7, LEGACY_VERSION) => ConfigureMultisig::try_from((payload, accounts))?.process(),
+        (8, LEGACY_VERSION) => SetBeneficiary::try_from((payload, accounts))?.process(),
+        (9, LEGACY_VERSION) => Claim::try_from(accounts)?.process(),
+        (10, LEGACY_VERSION) => DepositFor::try_from((payload, accounts))?.process(),
+        (10, 1) => DepositFor::try_from_v1((payload, accounts))?.process(),
+        (11, LEGACY_VERSION) => SetHook::try_from((payload, accounts))?.process(),
+        (12, LEGACY_VERSION) => WithdrawTo::try_from((payload, accounts))?.process(),
+        (13, LEGACY_VERSION) => WithdrawSigned::try_from((payload, accounts))?.process(),
+        (14, LEGACY_VERSION) => AddAllowlistEntry::try_from((payload, accounts))?.process(),
+        (15, LEGACY_VERSION) => RemoveAllowlistEntry::try_from((payload, accounts))?.process(),
+        (16, LEGACY_VERSION) => SetWithdrawCap::try_from((payload, accounts))?.process(),
+        (17, LEGACY_VERSION) => CreateStream::try_from((payload, accounts))?.process(),
+        (18, LEGACY_VERSION) => ClaimStream::try_from(accounts)?.process(),
+        (19, LEGACY_VERSION) => CancelStream::try_from(accounts)?.process(),
+        (20, LEGACY_VERSION) => Close::try_from(accounts)?.process(),
+        (21, LEGACY_VERSION) => InitializeConfig::try_from((payload, accounts))?.process(),
+        (22, LEGACY_VERSION) => UpdateConfig::try_from((payload, accounts))?.process(),
+        (23, LEGACY_VERSION) => DelegateStake::try_from((payload, accounts))?.process(),
+        (24, LEGACY_VERSION) => DeactivateStake::try_from(accounts)?.process(),
+        (25, LEGACY_VERSION) => WithdrawStake::try_from(accounts)?.process(),
+        (_, LEGACY_VERSION) => Err(VaultError::InvalidInstruction.into()),
+        _ => Err(VaultError::UnsupportedVersion.into()),
     }
 }
@@ -0,0 +1,43 @@
+use pinocchio::{account::AccountView, address::Address, cpi::Signer, ProgramResult};
+
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount, Transfer};
+
+/// Whether `account` is still a plain system account with no data, i.e. it
+/// has never been created by this program. Lamports alone say nothing: anyone
+/// can send lamports to a PDA before it is created.
+pub fn is_unallocated(account: &AccountView) -> bool {
+    account.owned_by(&pinocchio_system::ID) && account.data_len() == 0
+}
+
+/// Creates `account` at a PDA with `space` bytes owned by `owner`, funded by
+/// `payer` up to `lamports`.
+///
+/// `CreateAccount` fails on an account that already holds lamports, so a PDA
+/// that was pre-funded is instead topped up to `lamports` and then allocated
+/// and assigned, which the system program allows for funded accounts. The
+/// `signers` must include the PDA seeds, and the payer seeds too when the
+/// payer is itself a PDA.
+pub fn create_pda_account(
+    payer: &AccountView,
+    account: &AccountView,
+    lamports: u64,
+    space: u64,
+    owner: &Address,
+    signers: &[Signer],
+) -> ProgramResult {
+    let current = account.lamports();
+
+    if current == 0 {
+        return CreateAccount { from: payer, to: account, lamports, space, owner }
+            .invoke_signed(signers);
+    }
+
+    let shortfall = lamports.saturating_sub(current);
+    if shortfall != 0 {
+        Transfer { from: payer, to: account, lamports: shortfall }.invoke_signed(signers)?;
+    }
+
+    Allocate { account, space }.invoke_signed(signers)?;
+
+    Assign { account, owner }.invoke_signed(signers)
+}
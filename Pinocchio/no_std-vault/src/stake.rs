@@ -0,0 +1,181 @@
+use pinocchio::{
+    account::AccountView,
+    address::Address,
+    cpi::{invoke, invoke_signed, Signer},
+    instruction::{InstructionAccount, InstructionView},
+    ProgramResult,
+};
+
+/// Native stake program (`Stake11111111111111111111111111111111111111`)
+pub const STAKE_PROGRAM_ID: Address = Address::new_from_array([
+    0x06, 0xa1, 0xd8, 0x17, 0x91, 0x37, 0x54, 0x2a, 0x98, 0x34, 0x37, 0xbd, 0xfe, 0x2a, 0x7a, 0xb2,
+    0x55, 0x7f, 0x53, 0x5c, 0x8a, 0x78, 0x72, 0x2b, 0x68, 0xa4, 0x9d, 0xc0, 0x00, 0x00, 0x00, 0x00,
+]);
+
+/// Legacy stake config (`StakeConfig11111111111111111111111111111111`),
+/// still part of the `DelegateStake` account list
+pub const STAKE_CONFIG_ID: Address = Address::new_from_array([
+    0x06, 0xa1, 0xd8, 0x17, 0xa5, 0x02, 0x05, 0x0b, 0x68, 0x07, 0x91, 0xe6, 0xce, 0x6d, 0xb8, 0x8e,
+    0x1e, 0x5b, 0x71, 0x50, 0xf6, 0x1f, 0xc6, 0x79, 0x0a, 0x4e, 0xb4, 0xd1, 0x00, 0x00, 0x00, 0x00,
+]);
+
+/// Clock sysvar (`SysvarC1ock11111111111111111111111111111111`)
+pub const CLOCK_SYSVAR_ID: Address = Address::new_from_array([
+    0x06, 0xa7, 0xd5, 0x17, 0x18, 0xc7, 0x74, 0xc9, 0x28, 0x56, 0x63, 0x98, 0x69, 0x1d, 0x5e, 0xb6,
+    0x8b, 0x5e, 0xb8, 0xa3, 0x9b, 0x4b, 0x6d, 0x5c, 0x73, 0x55, 0x5b, 0x21, 0x00, 0x00, 0x00, 0x00,
+]);
+
+/// Rent sysvar (`SysvarRent111111111111111111111111111111111`)
+pub const RENT_SYSVAR_ID: Address = Address::new_from_array([
+    0x06, 0xa7, 0xd5, 0x17, 0x19, 0x2c, 0x5c, 0x51, 0x21, 0x8c, 0xc9, 0x4c, 0x3d, 0x4a, 0xf1, 0x7f,
+    0x58, 0xda, 0xee, 0x08, 0x9b, 0xa1, 0xfd, 0x44, 0xe3, 0xdb, 0xd9, 0x8a, 0x00, 0x00, 0x00, 0x00,
+]);
+
+/// Stake history sysvar (`SysvarStakeHistory1111111111111111111111111`)
+pub const STAKE_HISTORY_SYSVAR_ID: Address = Address::new_from_array([
+    0x06, 0xa7, 0xd5, 0x17, 0x19, 0x35, 0x84, 0xd0, 0xfe, 0xed, 0x9b, 0xb3, 0x43, 0x1d, 0x13, 0x20,
+    0x6b, 0xe5, 0x44, 0x28, 0x1b, 0x57, 0xb8, 0x56, 0x6c, 0xc5, 0x37, 0x5f, 0xf4, 0x00, 0x00, 0x00,
+]);
+
+/// Seed of the vault's stake account PDA, `["stake", owner]`
+pub const STAKE_SEED: &[u8] = b"stake";
+
+/// Size of a stake account (`StakeStateV2`)
+pub const STAKE_ACCOUNT_LEN: usize = 200;
+
+/// `StakeInstruction` variant indices (bincode encodes them as u32 LE)
+const INITIALIZE: u32 = 0;
+const DELEGATE_STAKE: u32 = 2;
+const WITHDRAW: u32 = 4;
+const DEACTIVATE: u32 = 5;
+
+/// `Initialize` data: `[tag, staker, withdrawer, lockup]` where the lockup
+/// is `[unix_timestamp (i64), epoch (u64), custodian (32 bytes)]`
+const INITIALIZE_DATA_LEN: usize = 4 + 32 + 32 + 8 + 8 + 32;
+
+/// Initializes `stake` with `authority` as both staker and withdrawer and
+/// no lockup.
+///
+/// - accounts: `[stake (writable), rent sysvar]`
+pub fn initialize_stake(
+    stake: &AccountView,
+    rent: &AccountView,
+    authority: &Address,
+) -> ProgramResult {
+    let instruction_accounts = [
+        InstructionAccount::writable(stake.address()),
+        InstructionAccount::readonly(rent.address()),
+    ];
+
+    // Zeroed lockup: no timestamp, no epoch, no custodian
+    let mut data = [0u8; INITIALIZE_DATA_LEN];
+    data[..4].copy_from_slice(&INITIALIZE.to_le_bytes());
+    data[4..36].copy_from_slice(authority.as_ref());
+    data[36..68].copy_from_slice(authority.as_ref());
+
+    let instruction = InstructionView {
+        program_id: &STAKE_PROGRAM_ID,
+        accounts: &instruction_accounts,
+        data: &data,
+    };
+
+    invoke(&instruction, &[stake, rent])
+}
+
+/// Delegates `stake` to `vote`, signed by its stake `authority`.
+///
+/// - accounts: `[stake (writable), vote, clock, stake history, stake config,
+///   authority (signer)]`
+pub fn delegate_stake(
+    stake: &AccountView,
+    vote: &AccountView,
+    clock: &AccountView,
+    stake_history: &AccountView,
+    stake_config: &AccountView,
+    authority: &AccountView,
+    signers: &[Signer],
+) -> ProgramResult {
+    let instruction_accounts = [
+        InstructionAccount::writable(stake.address()),
+        InstructionAccount::readonly(vote.address()),
+        InstructionAccount::readonly(clock.address()),
+        InstructionAccount::readonly(stake_history.address()),
+        InstructionAccount::readonly(stake_config.address()),
+        InstructionAccount::readonly_signer(authority.address()),
+    ];
+
+    let instruction = InstructionView {
+        program_id: &STAKE_PROGRAM_ID,
+        accounts: &instruction_accounts,
+        data: &DELEGATE_STAKE.to_le_bytes(),
+    };
+
+    invoke_signed(
+        &instruction,
+        &[stake, vote, clock, stake_history, stake_config, authority],
+        signers,
+    )
+}
+
+/// Starts cooling down `stake`, signed by its stake `authority`.
+///
+/// - accounts: `[stake (writable), clock, authority (signer)]`
+pub fn deactivate_stake(
+    stake: &AccountView,
+    clock: &AccountView,
+    authority: &AccountView,
+    signers: &[Signer],
+) -> ProgramResult {
+    let instruction_accounts = [
+        InstructionAccount::writable(stake.address()),
+        InstructionAccount::readonly(clock.address()),
+        InstructionAccount::readonly_signer(authority.address()),
+    ];
+
+    let instruction = InstructionView {
+        program_id: &STAKE_PROGRAM_ID,
+        accounts: &instruction_accounts,
+        data: &DEACTIVATE.to_le_bytes(),
+    };
+
+    invoke_signed(&instruction, &[stake, clock, authority], signers)
+}
+
+/// Moves `lamports` out of `stake` into `recipient`, signed by its withdraw
+/// `authority`.
+///
+/// - accounts: `[stake (writable), recipient (writable), clock, stake history,
+///   authority (signer)]`
+pub fn withdraw_stake(
+    stake: &AccountView,
+    recipient: &AccountView,
+    clock: &AccountView,
+    stake_history: &AccountView,
+    authority: &AccountView,
+    lamports: u64,
+    signers: &[Signer],
+) -> ProgramResult {
+    let instruction_accounts = [
+        InstructionAccount::writable(stake.address()),
+        InstructionAccount::writable(recipient.address()),
+        InstructionAccount::readonly(clock.address()),
+        InstructionAccount::readonly(stake_history.address()),
+        InstructionAccount::readonly_signer(authority.address()),
+    ];
+
+    let mut data = [0u8; 12];
+    data[..4].copy_from_slice(&WITHDRAW.to_le_bytes());
+    data[4..].copy_from_slice(&lamports.to_le_bytes());
+
+    let instruction = InstructionView {
+        program_id: &STAKE_PROGRAM_ID,
+        accounts: &instruction_accounts,
+        data: &data,
+    };
+
+    invoke_signed(
+        &instruction,
+        &[stake, recipient, clock, stake_history, authority],
+        signers,
+    )
+}
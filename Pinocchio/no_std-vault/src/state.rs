@@ -0,0 +1,754 @@
+use pinocchio::{
+    account::AccountView, address::Address, error::ProgramError, sysvars::clock::Clock,
+    ProgramResult,
+};
+
+use crate::VaultError;
+
+/// Maximum number of addresses in a multisig vault's signer set
+pub const MAX_SIGNERS: usize = 8;
+
+/// Maximum number of destinations on a vault's withdrawal allowlist
+pub const MAX_ALLOWLIST: usize = 8;
+
+/// Seconds before a raised (or removed) withdrawal cap takes effect
+pub const WITHDRAW_CAP_DELAY: i64 = 24 * 60 * 60;
+
+/// Highest protocol deposit fee, in basis points (5%)
+pub const MAX_FEE_BPS: u16 = 500;
+
+/// Seconds before a raised protocol fee takes effect, so deposits signed
+/// without a `max_fee` are never charged more than the fee they saw
+pub const FEE_CHANGE_DELAY: i64 = 24 * 60 * 60;
+
+const EMPTY_ADDRESS: Address = Address::new_from_array([0; 32]);
+
+/// Program-owned record of a vault's activity, stored at the
+/// `["state", owner]` PDA.
+///
+/// Every field is byte-aligned so the account data can be reinterpreted in
+/// place without alignment issues.
+///
+/// The leading `version` byte identifies the layout. Later changes either
+/// append fields under the same version, or bump [`VaultState::VERSION`] and
+/// realloc existing accounts to the new layout.
+#[repr(C)]
+pub struct VaultState {
+    pub version: u8,
+    pub owner: Address,
+    total_deposited: [u8; 8],
+    total_withdrawn: [u8; 8],
+    last_activity_slot: [u8; 8],
+    unlock_timestamp: [u8; 8],
+    signers: [Address; MAX_SIGNERS],
+    pub signer_count: u8,
+    pub threshold: u8,
+    beneficiary: Address,
+    inactivity_period: [u8; 8],
+    last_heartbeat: [u8; 8],
+    hook: Address,
+    nonce: [u8; 8],
+    init_slot: [u8; 8],
+    allowlist: [Address; MAX_ALLOWLIST],
+    allowlist_active_at: [[u8; 8]; MAX_ALLOWLIST],
+    pub allowlist_count: u8,
+    withdraw_cap: [u8; 8],
+    pending_withdraw_cap: [u8; 8],
+    pending_withdraw_cap_at: [u8; 8],
+    limit_epoch: [u8; 8],
+    epoch_withdrawn: [u8; 8],
+    pub bump: u8,
+}
+
+impl VaultState {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+    pub const SEED: &'static [u8] = b"state";
+    pub const VERSION: u8 = 1;
+
+    pub fn from_bytes(data: &[u8]) -> Result<&Self, ProgramError> {
+        if data.len() != Self::LEN || data[0] != Self::VERSION {
+            return Err(VaultError::StateNotInitialized.into());
+        }
+        // Safe: all fields are byte-aligned
+        Ok(unsafe { &*(data.as_ptr() as *const Self) })
+    }
+
+    pub fn from_bytes_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if data.len() != Self::LEN || data[0] != Self::VERSION {
+            return Err(VaultError::StateNotInitialized.into());
+        }
+        // Safe: all fields are byte-aligned
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
+    }
+
+    /// Reinterprets freshly allocated account data, before [`init`](Self::init)
+    /// has written the version
+    pub fn from_uninit_bytes_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(VaultError::StateNotInitialized.into());
+        }
+        // Safe: all fields are byte-aligned
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
+    }
+
+    /// Checks that `account` is an initialized state account belonging to `owner`
+    pub fn check(account: &AccountView, owner: &Address) -> Result<(), ProgramError> {
+        if !account.owned_by(&crate::ID) {
+            return Err(VaultError::StateNotInitialized.into());
+        }
+
+        let data = account.try_borrow()?;
+        if &Self::from_bytes(&data)?.owner != owner {
+            return Err(VaultError::InvalidState.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn init(&mut self, owner: &Address, bump: u8, clock: &Clock, unlock_timestamp: i64) {
+        self.version = Self::VERSION;
+        self.owner = *owner;
+        self.total_deposited = [0; 8];
+        self.total_withdrawn = [0; 8];
+        self.last_activity_slot = clock.slot.to_le_bytes();
+        self.unlock_timestamp = unlock_timestamp.to_le_bytes();
+        self.signers = [EMPTY_ADDRESS; MAX_SIGNERS];
+        self.signer_count = 0;
+        self.threshold = 0;
+        self.beneficiary = EMPTY_ADDRESS;
+        self.inactivity_period = [0; 8];
+        self.last_heartbeat = clock.unix_timestamp.to_le_bytes();
+        self.hook = EMPTY_ADDRESS;
+        self.nonce = [0; 8];
+        self.init_slot = clock.slot.to_le_bytes();
+        self.allowlist = [EMPTY_ADDRESS; MAX_ALLOWLIST];
+        self.allowlist_active_at = [[0; 8]; MAX_ALLOWLIST];
+        self.allowlist_count = 0;
+        self.withdraw_cap = [0; 8];
+        self.pending_withdraw_cap = [0; 8];
+        self.pending_withdraw_cap_at = [0; 8];
+        self.limit_epoch = clock.epoch.to_le_bytes();
+        self.epoch_withdrawn = [0; 8];
+        self.bump = bump;
+    }
+
+    pub fn total_deposited(&self) -> u64 {
+        u64::from_le_bytes(self.total_deposited)
+    }
+
+    pub fn total_withdrawn(&self) -> u64 {
+        u64::from_le_bytes(self.total_withdrawn)
+    }
+
+    pub fn last_activity_slot(&self) -> u64 {
+        u64::from_le_bytes(self.last_activity_slot)
+    }
+
+    pub fn set_last_activity_slot(&mut self, slot: u64) {
+        self.last_activity_slot = slot.to_le_bytes();
+    }
+
+    pub fn unlock_timestamp(&self) -> i64 {
+        i64::from_le_bytes(self.unlock_timestamp)
+    }
+
+    /// Moves the unlock time later; a lock can never be shortened
+    pub fn extend_lock(&mut self, unlock_timestamp: i64) -> ProgramResult {
+        if unlock_timestamp < self.unlock_timestamp() {
+            return Err(VaultError::LockShortened.into());
+        }
+
+        self.unlock_timestamp = unlock_timestamp.to_le_bytes();
+
+        Ok(())
+    }
+
+    /// Fails until the clock reaches the unlock time
+    pub fn check_unlocked(&self, now: i64) -> ProgramResult {
+        if now < self.unlock_timestamp() {
+            return Err(VaultError::VaultLocked.into());
+        }
+
+        Ok(())
+    }
+
+    /// Fails while closing would let a re-initialization drop a protection:
+    /// an allowlist or withdrawal cap is configured, or the vault was
+    /// initialized in `slot`, which the next [`init`](Self::init) would
+    /// record again as its [`init_slot`](Self::init_slot)
+    pub fn check_closable(&self, slot: u64) -> ProgramResult {
+        if self.allowlist_count != 0
+            || self.withdraw_cap() != 0
+            || self.pending_withdraw_cap().is_some()
+            || slot == self.init_slot()
+        {
+            return Err(VaultError::VaultNotClosable.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn signers(&self) -> &[Address] {
+        &self.signers[..self.signer_count as usize]
+    }
+
+    pub fn is_multisig(&self) -> bool {
+        self.threshold != 0
+    }
+
+    /// Replaces the signer set; a zero threshold with no signers turns
+    /// multisig mode off
+    pub fn set_multisig(&mut self, threshold: u8, signers: &[Address]) -> ProgramResult {
+        if signers.len() > MAX_SIGNERS || threshold as usize > signers.len() {
+            return Err(VaultError::InvalidMultisig.into());
+        }
+
+        if threshold == 0 && !signers.is_empty() {
+            return Err(VaultError::InvalidMultisig.into());
+        }
+
+        for (i, signer) in signers.iter().enumerate() {
+            if signers[..i].contains(signer) {
+                return Err(VaultError::InvalidMultisig.into());
+            }
+        }
+
+        self.signers = [EMPTY_ADDRESS; MAX_SIGNERS];
+        self.signers[..signers.len()].clone_from_slice(signers);
+        self.signer_count = signers.len() as u8;
+        self.threshold = threshold;
+
+        Ok(())
+    }
+
+    /// Counts distinct members of the signer set that signed among `owner`
+    /// and the trailing `remaining` accounts, and fails unless the threshold
+    /// is met. Always passes outside multisig mode.
+    pub fn check_signers(&self, owner: &AccountView, remaining: &[AccountView]) -> ProgramResult {
+        if !self.is_multisig() {
+            return Ok(());
+        }
+
+        // One bit per signer set entry, so duplicates are only counted once
+        let mut signed: u16 = 0;
+
+        for account in core::iter::once(owner).chain(remaining).filter(|a| a.is_signer()) {
+            if let Some(index) = self.signers().iter().position(|s| s == account.address()) {
+                signed |= 1 << index;
+            }
+        }
+
+        if signed.count_ones() < self.threshold as u32 {
+            return Err(VaultError::NotEnoughSigners.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn beneficiary(&self) -> Option<&Address> {
+        (self.beneficiary != EMPTY_ADDRESS).then_some(&self.beneficiary)
+    }
+
+    pub fn inactivity_period(&self) -> i64 {
+        i64::from_le_bytes(self.inactivity_period)
+    }
+
+    pub fn last_heartbeat(&self) -> i64 {
+        i64::from_le_bytes(self.last_heartbeat)
+    }
+
+    /// Marks the owner as alive; called by every owner-signed instruction
+    pub fn heartbeat(&mut self, now: i64) {
+        self.last_heartbeat = now.to_le_bytes();
+    }
+
+    /// Sets the recovery beneficiary; `None` removes it
+    pub fn set_beneficiary(
+        &mut self,
+        beneficiary: Option<&Address>,
+        inactivity_period: i64,
+    ) -> ProgramResult {
+        match beneficiary {
+            Some(beneficiary) => {
+                if inactivity_period <= 0 || beneficiary == &EMPTY_ADDRESS {
+                    return Err(VaultError::InvalidBeneficiary.into());
+                }
+                self.beneficiary = *beneficiary;
+                self.inactivity_period = inactivity_period.to_le_bytes();
+            }
+            None => {
+                self.beneficiary = EMPTY_ADDRESS;
+                self.inactivity_period = [0; 8];
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fails unless `claimant` is the beneficiary and the owner has been
+    /// inactive for the whole inactivity period
+    pub fn check_claimable(&self, claimant: &Address, now: i64) -> ProgramResult {
+        if self.beneficiary() != Some(claimant) {
+            return Err(VaultError::InvalidBeneficiary.into());
+        }
+
+        let deadline = self
+            .last_heartbeat()
+            .checked_add(self.inactivity_period())
+            .ok_or(VaultError::Overflow)?;
+
+        if now < deadline {
+            return Err(VaultError::OwnerStillActive.into());
+        }
+
+        Ok(())
+    }
+
+    /// Program notified after every lamport deposit, if any
+    pub fn hook(&self) -> Option<&Address> {
+        (self.hook != EMPTY_ADDRESS).then_some(&self.hook)
+    }
+
+    /// Registers the deposit hook program; `None` removes it
+    pub fn set_hook(&mut self, hook: Option<&Address>) -> ProgramResult {
+        match hook {
+            // Calling back into the vault would re-enter the deposit
+            Some(hook) if hook == &crate::ID => return Err(VaultError::InvalidHook.into()),
+            Some(hook) => self.hook = *hook,
+            None => self.hook = EMPTY_ADDRESS,
+        }
+
+        Ok(())
+    }
+
+    /// Nonce the next off-chain signed withdrawal must carry
+    pub fn nonce(&self) -> u64 {
+        u64::from_le_bytes(self.nonce)
+    }
+
+    /// Slot of the last [`init`](Self::init). Signed withdrawals cover it, so
+    /// authorizations from before a Close and re-initialization, when the
+    /// nonce restarts at zero, no longer verify.
+    pub fn init_slot(&self) -> u64 {
+        u64::from_le_bytes(self.init_slot)
+    }
+
+    /// Consumes `nonce` so a signed withdrawal can never be replayed
+    pub fn use_nonce(&mut self, nonce: u64) -> ProgramResult {
+        if nonce != self.nonce() {
+            return Err(VaultError::InvalidNonce.into());
+        }
+
+        let next = nonce.checked_add(1).ok_or(VaultError::Overflow)?;
+        self.nonce = next.to_le_bytes();
+
+        Ok(())
+    }
+
+    /// Destinations withdrawals may pay out to; empty means unrestricted
+    pub fn allowlist(&self) -> &[Address] {
+        &self.allowlist[..self.allowlist_count as usize]
+    }
+
+    /// Unix timestamp from which the allowlist entry at `index` can be used
+    pub fn allowlist_active_at(&self, index: usize) -> i64 {
+        i64::from_le_bytes(self.allowlist_active_at[index])
+    }
+
+    pub fn add_allowlist_entry(&mut self, destination: &Address, active_at: i64) -> ProgramResult {
+        if destination == &EMPTY_ADDRESS || self.allowlist().contains(destination) {
+            return Err(VaultError::InvalidAllowlistEntry.into());
+        }
+
+        let index = self.allowlist_count as usize;
+        if index == MAX_ALLOWLIST {
+            return Err(VaultError::AllowlistFull.into());
+        }
+
+        self.allowlist[index] = *destination;
+        self.allowlist_active_at[index] = active_at.to_le_bytes();
+        self.allowlist_count += 1;
+
+        Ok(())
+    }
+
+    /// Removes `destination`, moving the last entry into its slot. The last
+    /// entry can never be removed: emptying the list would lift the
+    /// restriction without any delay.
+    pub fn remove_allowlist_entry(&mut self, destination: &Address) -> ProgramResult {
+        let index = self
+            .allowlist()
+            .iter()
+            .position(|entry| entry == destination)
+            .ok_or(VaultError::InvalidAllowlistEntry)?;
+
+        if self.allowlist_count == 1 {
+            return Err(VaultError::InvalidAllowlistEntry.into());
+        }
+
+        let last = self.allowlist_count as usize - 1;
+        self.allowlist[index] = self.allowlist[last];
+        self.allowlist_active_at[index] = self.allowlist_active_at[last];
+        self.allowlist[last] = EMPTY_ADDRESS;
+        self.allowlist_active_at[last] = [0; 8];
+        self.allowlist_count -= 1;
+
+        Ok(())
+    }
+
+    /// Fails unless `destination` is an active allowlist entry. Always passes
+    /// while the allowlist is empty.
+    pub fn check_allowlisted(&self, destination: &Address, now: i64) -> ProgramResult {
+        if self.allowlist_count == 0 {
+            return Ok(());
+        }
+
+        let index = self
+            .allowlist()
+            .iter()
+            .position(|entry| entry == destination)
+            .ok_or(VaultError::DestinationNotAllowed)?;
+
+        if now < self.allowlist_active_at(index) {
+            return Err(VaultError::AllowlistEntryPending.into());
+        }
+
+        Ok(())
+    }
+
+    /// Lamports withdrawable per epoch; zero means unlimited
+    pub fn withdraw_cap(&self) -> u64 {
+        u64::from_le_bytes(self.withdraw_cap)
+    }
+
+    /// Raised cap waiting for its delay, as `(cap, active_at)`
+    pub fn pending_withdraw_cap(&self) -> Option<(u64, i64)> {
+        let active_at = i64::from_le_bytes(self.pending_withdraw_cap_at);
+        (active_at != 0).then(|| (u64::from_le_bytes(self.pending_withdraw_cap), active_at))
+    }
+
+    pub fn limit_epoch(&self) -> u64 {
+        u64::from_le_bytes(self.limit_epoch)
+    }
+
+    /// Lamports withdrawn during [`Self::limit_epoch`]
+    pub fn epoch_withdrawn(&self) -> u64 {
+        u64::from_le_bytes(self.epoch_withdrawn)
+    }
+
+    /// Makes a pending raise whose delay has elapsed by `now` the current cap
+    fn apply_pending_withdraw_cap(&mut self, now: i64) {
+        if let Some((cap, active_at)) = self.pending_withdraw_cap() {
+            if now >= active_at {
+                self.withdraw_cap = cap.to_le_bytes();
+                self.pending_withdraw_cap = [0; 8];
+                self.pending_withdraw_cap_at = [0; 8];
+            }
+        }
+    }
+
+    /// Tightening the cap in force at `now` applies immediately and cancels
+    /// any pending raise; raising or removing it only applies after
+    /// [`WITHDRAW_CAP_DELAY`]
+    pub fn set_withdraw_cap(&mut self, cap: u64, now: i64) -> ProgramResult {
+        self.apply_pending_withdraw_cap(now);
+
+        let current = self.withdraw_cap();
+        let raises = current != 0 && (cap == 0 || cap > current);
+
+        if raises {
+            let active_at = now.checked_add(WITHDRAW_CAP_DELAY).ok_or(VaultError::Overflow)?;
+            self.pending_withdraw_cap = cap.to_le_bytes();
+            self.pending_withdraw_cap_at = active_at.to_le_bytes();
+        } else {
+            self.withdraw_cap = cap.to_le_bytes();
+            self.pending_withdraw_cap = [0; 8];
+            self.pending_withdraw_cap_at = [0; 8];
+        }
+
+        Ok(())
+    }
+
+    /// Counts `amount` against the current epoch's allowance, starting a
+    /// fresh allowance whenever the epoch changes
+    pub fn consume_withdraw_limit(&mut self, amount: u64, clock: &Clock) -> ProgramResult {
+        self.apply_pending_withdraw_cap(clock.unix_timestamp);
+
+        if clock.epoch != self.limit_epoch() {
+            self.limit_epoch = clock.epoch.to_le_bytes();
+            self.epoch_withdrawn = [0; 8];
+        }
+
+        let withdrawn = self
+            .epoch_withdrawn()
+            .checked_add(amount)
+            .ok_or(VaultError::Overflow)?;
+
+        let cap = self.withdraw_cap();
+        if cap != 0 && withdrawn > cap {
+            return Err(VaultError::WithdrawLimitExceeded.into());
+        }
+
+        self.epoch_withdrawn = withdrawn.to_le_bytes();
+
+        Ok(())
+    }
+
+    pub fn record_deposit(&mut self, amount: u64, slot: u64) -> ProgramResult {
+        let total = self
+            .total_deposited()
+            .checked_add(amount)
+            .ok_or(VaultError::Overflow)?;
+
+        self.total_deposited = total.to_le_bytes();
+        self.set_last_activity_slot(slot);
+
+        Ok(())
+    }
+
+    pub fn record_withdrawal(&mut self, amount: u64, slot: u64) -> ProgramResult {
+        let total = self
+            .total_withdrawn()
+            .checked_add(amount)
+            .ok_or(VaultError::Overflow)?;
+
+        self.total_withdrawn = total.to_le_bytes();
+        self.set_last_activity_slot(slot);
+
+        Ok(())
+    }
+}
+
+/// Linear lamport stream from an owner to a recipient, stored at the
+/// `["stream", owner, recipient]` PDA. The streamed lamports sit in a
+/// system-owned escrow at `["stream_escrow", stream]`.
+#[repr(C)]
+pub struct Stream {
+    pub owner: Address,
+    pub recipient: Address,
+    amount: [u8; 8],
+    claimed: [u8; 8],
+    start_time: [u8; 8],
+    end_time: [u8; 8],
+    pub bump: u8,
+    pub escrow_bump: u8,
+}
+
+impl Stream {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+    pub const SEED: &'static [u8] = b"stream";
+    pub const ESCROW_SEED: &'static [u8] = b"stream_escrow";
+
+    pub fn from_bytes(data: &[u8]) -> Result<&Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(VaultError::InvalidStream.into());
+        }
+        // Safe: all fields are byte-aligned
+        Ok(unsafe { &*(data.as_ptr() as *const Self) })
+    }
+
+    pub fn from_bytes_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(VaultError::InvalidStream.into());
+        }
+        // Safe: all fields are byte-aligned
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
+    }
+
+    /// Checks that `account` is a live stream from `owner` to `recipient`
+    pub fn check(account: &AccountView, owner: &Address, recipient: &Address) -> ProgramResult {
+        if !account.owned_by(&crate::ID) {
+            return Err(VaultError::InvalidStream.into());
+        }
+
+        let data = account.try_borrow()?;
+        let stream = Self::from_bytes(&data)?;
+        if &stream.owner != owner || &stream.recipient != recipient {
+            return Err(VaultError::InvalidStream.into());
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn init(
+        &mut self,
+        owner: &Address,
+        recipient: &Address,
+        amount: u64,
+        start_time: i64,
+        end_time: i64,
+        bump: u8,
+        escrow_bump: u8,
+    ) {
+        self.owner = *owner;
+        self.recipient = *recipient;
+        self.amount = amount.to_le_bytes();
+        self.claimed = [0; 8];
+        self.start_time = start_time.to_le_bytes();
+        self.end_time = end_time.to_le_bytes();
+        self.bump = bump;
+        self.escrow_bump = escrow_bump;
+    }
+
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    pub fn claimed(&self) -> u64 {
+        u64::from_le_bytes(self.claimed)
+    }
+
+    pub fn start_time(&self) -> i64 {
+        i64::from_le_bytes(self.start_time)
+    }
+
+    pub fn end_time(&self) -> i64 {
+        i64::from_le_bytes(self.end_time)
+    }
+
+    /// Lamports vested at `now`, growing linearly from `start_time` to `end_time`
+    pub fn vested(&self, now: i64) -> u64 {
+        let (start, end) = (self.start_time(), self.end_time());
+
+        if now <= start {
+            0
+        } else if now >= end {
+            self.amount()
+        } else {
+            // Differences in i128 so that any pair of i64 times fits; both
+            // are positive here and the product of two u64s fits in a u128
+            let elapsed = (now as i128 - start as i128) as u128;
+            let duration = (end as i128 - start as i128) as u128;
+
+            // elapsed < duration, so the result is below `amount`
+            (self.amount() as u128 * elapsed / duration) as u64
+        }
+    }
+
+    /// Vested lamports the recipient has not claimed yet
+    pub fn claimable(&self, now: i64) -> u64 {
+        self.vested(now).saturating_sub(self.claimed())
+    }
+
+    pub fn record_claim(&mut self, amount: u64) -> ProgramResult {
+        let claimed = self.claimed().checked_add(amount).ok_or(VaultError::Overflow)?;
+        self.claimed = claimed.to_le_bytes();
+
+        Ok(())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.claimed() == self.amount()
+    }
+
+    /// Closes a finished stream account, returning its rent to `destination`
+    pub fn close(account: &AccountView, destination: &AccountView) -> ProgramResult {
+        let lamports = destination
+            .lamports()
+            .checked_add(account.lamports())
+            .ok_or(VaultError::Overflow)?;
+
+        destination.set_lamports(lamports);
+        account.set_lamports(0);
+
+        account.close()
+    }
+}
+
+/// Protocol-wide settings stored at the `["config"]` PDA
+#[repr(C)]
+pub struct Config {
+    pub admin: Address,
+    pub treasury: Address,
+    fee_bps: [u8; 2],
+    pending_fee_bps: [u8; 2],
+    pending_fee_bps_at: [u8; 8],
+    pub bump: u8,
+}
+
+impl Config {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+    pub const SEED: &'static [u8] = b"config";
+
+    pub fn from_bytes(data: &[u8]) -> Result<&Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(VaultError::InvalidConfig.into());
+        }
+        // Safe: all fields are byte-aligned
+        Ok(unsafe { &*(data.as_ptr() as *const Self) })
+    }
+
+    pub fn from_bytes_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(VaultError::InvalidConfig.into());
+        }
+        // Safe: all fields are byte-aligned
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
+    }
+
+    /// Fee charged at `now`, including a raise whose delay has elapsed
+    pub fn fee_bps(&self, now: i64) -> u16 {
+        match self.pending_fee_bps() {
+            Some((fee_bps, active_at)) if now >= active_at => fee_bps,
+            _ => u16::from_le_bytes(self.fee_bps),
+        }
+    }
+
+    /// Raised fee waiting for its delay, as `(fee_bps, active_at)`
+    pub fn pending_fee_bps(&self) -> Option<(u16, i64)> {
+        let active_at = i64::from_le_bytes(self.pending_fee_bps_at);
+        (active_at != 0).then(|| (u16::from_le_bytes(self.pending_fee_bps), active_at))
+    }
+
+    /// Sets up a fresh config; the initial fee applies immediately
+    pub fn init(&mut self, admin: &Address, treasury: &Address, fee_bps: u16, bump: u8) -> ProgramResult {
+        if fee_bps > MAX_FEE_BPS {
+            return Err(VaultError::FeeTooHigh.into());
+        }
+
+        self.admin = *admin;
+        self.treasury = *treasury;
+        self.fee_bps = fee_bps.to_le_bytes();
+        self.pending_fee_bps = [0; 2];
+        self.pending_fee_bps_at = [0; 8];
+        self.bump = bump;
+
+        Ok(())
+    }
+
+    /// The admin and treasury change immediately. Lowering or keeping the
+    /// fee applies immediately and cancels any pending raise; raising it only applies
+    /// after [`FEE_CHANGE_DELAY`]
+    pub fn set(&mut self, admin: &Address, treasury: &Address, fee_bps: u16, now: i64) -> ProgramResult {
+        if fee_bps > MAX_FEE_BPS {
+            return Err(VaultError::FeeTooHigh.into());
+        }
+
+        let current = self.fee_bps(now);
+
+        if fee_bps > current {
+            let active_at = now.checked_add(FEE_CHANGE_DELAY).ok_or(VaultError::Overflow)?;
+            self.fee_bps = current.to_le_bytes();
+            self.pending_fee_bps = fee_bps.to_le_bytes();
+            self.pending_fee_bps_at = active_at.to_le_bytes();
+        } else {
+            self.fee_bps = fee_bps.to_le_bytes();
+            self.pending_fee_bps = [0; 2];
+            self.pending_fee_bps_at = [0; 8];
+        }
+
+        self.admin = *admin;
+        self.treasury = *treasury;
+
+        Ok(())
+    }
+
+    /// Protocol share of a deposit of `amount` lamports at `now`
+    pub fn fee(&self, amount: u64, now: i64) -> Result<u64, ProgramError> {
+        // u128 so that large deposits cannot overflow the product
+        let fee = amount as u128 * self.fee_bps(now) as u128 / 10_000;
+
+        u64::try_from(fee).map_err(|_| VaultError::Overflow.into())
+    }
+}
@@ -0,0 +1,1657 @@
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use crate::{client, VaultError};
+    use litesvm::{types::TransactionResult, LiteSVM};
+    use solana_sdk::{
+        account::Account,
+        ed25519_instruction::new_ed25519_instruction_with_signature,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program,
+        sysvar::{self, clock::Clock},
+        transaction::{Transaction, TransactionError},
+    };
+    use solana_vote_interface::state::{VoteInit, VoteState, VoteStateVersions};
+
+    const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+    /// Built by `cargo build-sbf`, which has to run before these tests
+    const PROGRAM_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/deploy/blueshift_vault.so");
+
+    /// Fee destination passed by the deposit helpers
+    const TREASURY: Pubkey = Pubkey::new_from_array([7; 32]);
+
+    fn program_id() -> Pubkey {
+        Pubkey::new_from_array(crate::ID.to_bytes())
+    }
+
+    fn program_bytes() -> std::vec::Vec<u8> {
+        std::fs::read(PROGRAM_PATH)
+            .unwrap_or_else(|_| panic!("{PROGRAM_PATH} is missing, run `cargo build-sbf` first"))
+    }
+
+    fn setup() -> (LiteSVM, Keypair) {
+        let mut svm = LiteSVM::new();
+
+        // Load the program
+        svm.add_program(program_id(), &program_bytes());
+
+        // Create a user with some SOL
+        let user = Keypair::new();
+        svm.airdrop(&user.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+        (svm, user)
+    }
+
+    fn get_vault_pda(owner: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"vault", owner.as_ref()], &program_id())
+    }
+
+    fn get_state_pda(owner: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"state", owner.as_ref()], &program_id())
+    }
+
+    fn get_stake_pda(owner: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"stake", owner.as_ref()], &program_id())
+    }
+
+    fn get_config_pda() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"config"], &program_id())
+    }
+
+    fn create_initialize_ix(owner: &Pubkey, unlock_timestamp: i64) -> Instruction {
+        let mut data = vec![3];
+        data.extend_from_slice(&unlock_timestamp.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*owner, true),
+                AccountMeta::new(get_state_pda(owner).0, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data,
+        }
+    }
+
+    fn create_deposit_ix(owner: &Pubkey, vault: &Pubkey, amount: u64) -> Instruction {
+        let mut data = vec![0];
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*owner, true),
+                AccountMeta::new(*vault, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new(get_state_pda(owner).0, false),
+                AccountMeta::new_readonly(get_config_pda().0, false),
+                AccountMeta::new(TREASURY, false),
+            ],
+            data,
+        }
+    }
+
+    fn create_deposit_for_ix(payer: &Pubkey, owner: &Pubkey, vault: &Pubkey, amount: u64) -> Instruction {
+        let mut data = vec![10];
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*payer, true),
+                AccountMeta::new_readonly(*owner, false),
+                AccountMeta::new(*vault, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new(get_state_pda(owner).0, false),
+                AccountMeta::new_readonly(get_config_pda().0, false),
+                AccountMeta::new(TREASURY, false),
+            ],
+            data,
+        }
+    }
+
+    fn create_withdraw_ix(owner: &Pubkey, vault: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*owner, true),
+                AccountMeta::new(*vault, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new(get_state_pda(owner).0, false),
+            ],
+            data: vec![1],
+        }
+    }
+
+    fn create_close_ix(owner: &Pubkey, vault: &Pubkey) -> Instruction {
+        let mut ix = create_withdraw_ix(owner, vault);
+        ix.accounts.push(AccountMeta::new_readonly(get_stake_pda(owner).0, false));
+        ix.data = vec![20];
+        ix
+    }
+
+    fn create_withdraw_to_ix(owner: &Pubkey, vault: &Pubkey, recipient: &Pubkey, amount: u64) -> Instruction {
+        let mut data = vec![12];
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*recipient, false),
+                AccountMeta::new(*owner, true),
+                AccountMeta::new(*vault, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new(get_state_pda(owner).0, false),
+            ],
+            data,
+        }
+    }
+
+    /// The owner's off-chain authorization followed by the relayed withdrawal
+    fn create_withdraw_signed_ixs(
+        owner: &Keypair,
+        vault: &Pubkey,
+        recipient: &Pubkey,
+        init_slot: u64,
+        amount: u64,
+        nonce: u64,
+        expiry: i64,
+    ) -> [Instruction; 2] {
+        let mut data = vec![13];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&nonce.to_le_bytes());
+        data.extend_from_slice(&expiry.to_le_bytes());
+
+        // Signed message is [vault, recipient, init_slot, amount, nonce, expiry]
+        let mut message = vault.to_bytes().to_vec();
+        message.extend_from_slice(recipient.as_ref());
+        message.extend_from_slice(&init_slot.to_le_bytes());
+        message.extend_from_slice(&data[1..]);
+
+        let signature = owner.sign_message(&message);
+        let ed25519_ix = new_ed25519_instruction_with_signature(
+            &message,
+            signature.as_ref().try_into().unwrap(),
+            &owner.pubkey().to_bytes(),
+        );
+
+        let withdraw_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*recipient, false),
+                AccountMeta::new_readonly(sysvar::instructions::ID, false),
+                AccountMeta::new_readonly(owner.pubkey(), false),
+                AccountMeta::new(*vault, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new(get_state_pda(&owner.pubkey()).0, false),
+            ],
+            data,
+        };
+
+        [ed25519_ix, withdraw_ix]
+    }
+
+    fn create_add_allowlist_entry_ix(owner: &Pubkey, destination: &Pubkey, activation_delay: i64) -> Instruction {
+        let mut data = vec![14];
+        data.extend_from_slice(destination.as_ref());
+        data.extend_from_slice(&activation_delay.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new(get_state_pda(owner).0, false),
+            ],
+            data,
+        }
+    }
+
+    fn create_set_withdraw_cap_ix(owner: &Pubkey, cap: u64) -> Instruction {
+        let mut data = vec![16];
+        data.extend_from_slice(&cap.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new(get_state_pda(owner).0, false),
+            ],
+            data,
+        }
+    }
+
+    fn create_stream_ix(data: Vec<u8>, owner: &Pubkey, recipient: &Pubkey, owner_signs: bool) -> Instruction {
+        let (stream, _) =
+            Pubkey::find_program_address(&[b"stream", owner.as_ref(), recipient.as_ref()], &program_id());
+        let (escrow, _) = Pubkey::find_program_address(&[b"stream_escrow", stream.as_ref()], &program_id());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*owner, owner_signs),
+                AccountMeta::new(*recipient, !owner_signs),
+                AccountMeta::new(stream, false),
+                AccountMeta::new(escrow, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data,
+        }
+    }
+
+    fn create_set_beneficiary_ix(owner: &Pubkey, beneficiary: &Pubkey, period: i64) -> Instruction {
+        let mut data = vec![8];
+        data.extend_from_slice(beneficiary.as_ref());
+        data.extend_from_slice(&period.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new(get_state_pda(owner).0, false),
+            ],
+            data,
+        }
+    }
+
+    fn create_claim_ix(beneficiary: &Pubkey, owner: &Pubkey, vault: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*beneficiary, true),
+                AccountMeta::new_readonly(*owner, false),
+                AccountMeta::new(*vault, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new(get_state_pda(owner).0, false),
+            ],
+            data: vec![9],
+        }
+    }
+
+    fn create_delegate_stake_ix(owner: &Pubkey, vote: &Pubkey, amount: u64) -> Instruction {
+        let mut data = vec![23];
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(*vote, false),
+                AccountMeta::new_readonly(sysvar::stake_history::ID, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(crate::STAKE_CONFIG_ID.to_bytes()), false),
+                AccountMeta::new_readonly(sysvar::rent::ID, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new(get_vault_pda(owner).0, false),
+                AccountMeta::new(get_state_pda(owner).0, false),
+                AccountMeta::new(get_stake_pda(owner).0, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(crate::STAKE_PROGRAM_ID.to_bytes()), false),
+                AccountMeta::new_readonly(sysvar::clock::ID, false),
+            ],
+            data,
+        }
+    }
+
+    /// Makes `admin` the program's upgrade authority and creates the config
+    fn create_initialize_config_ix(svm: &mut LiteSVM, admin: &Pubkey, treasury: &Pubkey, fee_bps: u16) -> Instruction {
+        let loader = solana_sdk::bpf_loader_upgradeable::ID;
+        let program_data = Pubkey::find_program_address(&[program_id().as_ref()], &loader).0;
+
+        // ProgramData: [tag 3, slot, Some(authority), program bytes]
+        let mut data = vec![3, 0, 0, 0];
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(admin.as_ref());
+        data.extend_from_slice(&program_bytes());
+        svm.set_account(
+            program_data,
+            solana_sdk::account::Account {
+                lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+                data,
+                owner: loader,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        let mut data = vec![21];
+        data.extend_from_slice(treasury.as_ref());
+        data.extend_from_slice(&fee_bps.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*admin, true),
+                AccountMeta::new(get_config_pda().0, false),
+                AccountMeta::new_readonly(program_data, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data,
+        }
+    }
+
+    fn token_program_ids() -> [Pubkey; 2] {
+        [
+            Pubkey::new_from_array(pinocchio_token::ID.to_bytes()),
+            Pubkey::new_from_array(pinocchio_token_2022::ID.to_bytes()),
+        ]
+    }
+
+    /// Writes an initialized base-layout mint owned by `token_program`
+    fn create_mint(svm: &mut LiteSVM, token_program: &Pubkey, decimals: u8) -> Pubkey {
+        let mut data = vec![0; 82];
+        data[44] = decimals;
+        data[45] = 1;
+
+        let mint = Pubkey::new_unique();
+        let account = Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: *token_program,
+            executable: false,
+            rent_epoch: 0,
+        };
+        svm.set_account(mint, account).unwrap();
+        mint
+    }
+
+    /// Writes an initialized base-layout token account for `mint` owned by `authority`
+    fn create_token_account(
+        svm: &mut LiteSVM,
+        token_program: &Pubkey,
+        mint: &Pubkey,
+        authority: &Pubkey,
+        amount: u64,
+    ) -> Pubkey {
+        let mut data = vec![0; 165];
+        data[..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(authority.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data[108] = 1;
+
+        let token_account = Pubkey::new_unique();
+        let account = Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: *token_program,
+            executable: false,
+            rent_epoch: 0,
+        };
+        svm.set_account(token_account, account).unwrap();
+        token_account
+    }
+
+    fn token_balance(svm: &LiteSVM, token_account: &Pubkey) -> u64 {
+        let data = svm.get_account(token_account).unwrap().data;
+        u64::from_le_bytes(data[64..72].try_into().unwrap())
+    }
+
+    fn create_token_ix(
+        discriminator: u8,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        owner_token_account: &Pubkey,
+        vault_token_account: &Pubkey,
+        token_program: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        let mut data = vec![discriminator];
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new_readonly(get_vault_pda(owner).0, false),
+                AccountMeta::new(get_state_pda(owner).0, false),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new(*owner_token_account, false),
+                AccountMeta::new(*vault_token_account, false),
+                AccountMeta::new_readonly(*token_program, false),
+            ],
+            data,
+        }
+    }
+
+    fn address(pubkey: &Pubkey) -> pinocchio::address::Address {
+        pinocchio::address::Address::new_from_array(pubkey.to_bytes())
+    }
+
+    /// Converts a `client` instruction to the SDK type LiteSVM accepts
+    fn from_client(ix: solana_instruction::Instruction) -> Instruction {
+        Instruction {
+            program_id: Pubkey::new_from_array(ix.program_id.to_bytes()),
+            accounts: ix
+                .accounts
+                .into_iter()
+                .map(|meta| AccountMeta {
+                    pubkey: Pubkey::new_from_array(meta.pubkey.to_bytes()),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: ix.data,
+        }
+    }
+
+    /// `(is_signer, is_writable)` of every account, in order
+    fn flags(ix: &solana_instruction::Instruction) -> std::vec::Vec<(bool, bool)> {
+        ix.accounts.iter().map(|meta| (meta.is_signer, meta.is_writable)).collect()
+    }
+
+    /// Creates a vote account the stake program will accept as a delegation
+    /// target
+    fn create_vote_account(svm: &mut LiteSVM) -> Pubkey {
+        let node = Pubkey::new_unique();
+        let vote_init = VoteInit {
+            node_pubkey: node,
+            authorized_voter: node,
+            authorized_withdrawer: node,
+            commission: 0,
+        };
+        let vote_state = VoteState::new(&vote_init, &svm.get_sysvar::<Clock>());
+
+        let mut data = vec![0; VoteState::size_of()];
+        VoteState::serialize(&VoteStateVersions::new_current(vote_state), &mut data).unwrap();
+
+        let vote = Pubkey::new_unique();
+        let account = Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: solana_vote_interface::program::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        svm.set_account(vote, account).unwrap();
+        vote
+    }
+
+    /// Sends the rent-exempt minimum to `account` from a fresh third party,
+    /// as a griefer would to block a PDA from being created
+    fn prefund(svm: &mut LiteSVM, account: &Pubkey) {
+        let griefer = Keypair::new();
+        svm.airdrop(&griefer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let mut data = vec![2, 0, 0, 0];
+        data.extend_from_slice(&svm.minimum_balance_for_rent_exemption(0).to_le_bytes());
+        let ix = Instruction {
+            program_id: system_program::ID,
+            accounts: vec![AccountMeta::new(griefer.pubkey(), true), AccountMeta::new(*account, false)],
+            data,
+        };
+        send(svm, ix, &griefer).unwrap();
+    }
+
+    /// Checks a CPI builder's metas and data against the `client` instruction
+    fn assert_cpi_matches(
+        accounts: &[pinocchio::instruction::InstructionAccount],
+        data: &[u8],
+        expected: &solana_instruction::Instruction,
+    ) {
+        let metas: std::vec::Vec<_> = accounts
+            .iter()
+            .map(|account| (*account.address, account.is_signer, account.is_writable))
+            .collect();
+        let expected_metas: std::vec::Vec<_> = expected
+            .accounts
+            .iter()
+            .map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable))
+            .collect();
+
+        assert_eq!(metas, expected_metas);
+        assert_eq!(data, expected.data.as_slice());
+    }
+
+    fn send(svm: &mut LiteSVM, ix: Instruction, payer: &Keypair) -> TransactionResult {
+        let blockhash = svm.latest_blockhash();
+        let tx = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[payer], blockhash);
+        svm.send_transaction(tx)
+    }
+
+    fn assert_vault_error(result: TransactionResult, expected: VaultError) {
+        assert_vault_error_at(result, 0, expected);
+    }
+
+    fn assert_vault_error_at(result: TransactionResult, index: u8, expected: VaultError) {
+        let err = result.expect_err("transaction should fail").err;
+        assert_eq!(
+            err,
+            TransactionError::InstructionError(index, InstructionError::Custom(expected as u32))
+        );
+    }
+
+    fn initialized() -> (LiteSVM, Keypair, Pubkey) {
+        let (mut svm, user) = setup();
+        send(&mut svm, create_initialize_ix(&user.pubkey(), 0), &user).unwrap();
+        let (vault_pda, _bump) = get_vault_pda(&user.pubkey());
+        (svm, user, vault_pda)
+    }
+
+    #[test]
+    fn test_error_codes_round_trip() {
+        for code in 0..=VaultError::UnsupportedVersion as u32 {
+            assert_eq!(VaultError::try_from(code).unwrap() as u32, code);
+        }
+        assert_eq!(VaultError::try_from(VaultError::UnsupportedVersion as u32 + 1), Err(48));
+    }
+
+    #[test]
+    fn test_client_builders_round_trip() {
+        let (mut svm, user) = setup();
+        let owner = address(&user.pubkey());
+        let treasury = address(&TREASURY);
+        let (vault, _) = crate::find_vault_address(&owner);
+        let (state, _) = crate::find_state_address(&owner);
+        let now = svm.get_sysvar::<Clock>().unix_timestamp;
+
+        // Each builder's data parses back through the on-chain parser, and its
+        // accounts pass the on-chain account checks when sent
+        let ix = client::initialize(&owner, now);
+        assert_eq!(flags(&ix), [(true, true), (false, true), (false, false)]);
+        assert_eq!(ix.accounts[1].pubkey, state);
+        assert_eq!(crate::InitializeData::try_from(&ix.data[1..]).unwrap().unlock_timestamp, now);
+        assert!(send(&mut svm, from_client(ix), &user).is_ok(), "Initialize should succeed");
+
+        let ix = client::deposit(&owner, &treasury, LAMPORTS_PER_SOL);
+        assert_eq!(
+            flags(&ix),
+            [(true, true), (false, true), (false, false), (false, true), (false, false), (false, true)]
+        );
+        assert_eq!(ix.accounts[1].pubkey, vault);
+        assert_eq!(ix.accounts[4].pubkey, crate::find_config_address().0);
+        assert_eq!(ix.accounts[5].pubkey, treasury);
+        assert_eq!(crate::DepositData::try_from(&ix.data[1..]).unwrap().amount, LAMPORTS_PER_SOL);
+        assert!(send(&mut svm, from_client(ix), &user).is_ok(), "Deposit should succeed");
+
+        let friend = Keypair::new();
+        svm.airdrop(&friend.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let ix = client::deposit_for(&address(&friend.pubkey()), &owner, &treasury, LAMPORTS_PER_SOL / 2);
+        assert_eq!(
+            flags(&ix),
+            [(true, true), (false, false), (false, true), (false, false), (false, true), (false, false), (false, true)]
+        );
+        assert_eq!(ix.accounts[1].pubkey, owner);
+        assert_eq!(crate::DepositData::try_from(&ix.data[1..]).unwrap().amount, LAMPORTS_PER_SOL / 2);
+        assert!(send(&mut svm, from_client(ix), &friend).is_ok(), "Deposit for should succeed");
+
+        // Version 1 builders keep the accounts and add the envelope and max fee
+        let ix = client::deposit_with_max_fee(&owner, &treasury, LAMPORTS_PER_SOL / 4, 0);
+        assert_eq!(ix.data[..2], [0x80, 1]);
+        let data = crate::DepositData::try_from_v1(&ix.data[2..]).unwrap();
+        assert_eq!((data.amount, data.max_fee), (LAMPORTS_PER_SOL / 4, Some(0)));
+        assert!(send(&mut svm, from_client(ix), &user).is_ok(), "Deposit with max fee should succeed");
+
+        let ix = client::deposit_for_with_max_fee(&address(&friend.pubkey()), &owner, &treasury, LAMPORTS_PER_SOL / 4, 0);
+        assert_eq!(
+            flags(&ix),
+            [(true, true), (false, false), (false, true), (false, false), (false, true), (false, false), (false, true)]
+        );
+        assert_eq!(ix.data[..2], [0x8a, 1]);
+        let data = crate::DepositData::try_from_v1(&ix.data[2..]).unwrap();
+        assert_eq!((data.amount, data.max_fee), (LAMPORTS_PER_SOL / 4, Some(0)));
+        assert!(send(&mut svm, from_client(ix), &friend).is_ok(), "Deposit for with max fee should succeed");
+
+        let ix = client::partial_withdraw(&owner, LAMPORTS_PER_SOL / 4, &[]);
+        assert_eq!(flags(&ix), [(true, true), (false, true), (false, false), (false, true)]);
+        assert_eq!(crate::PartialWithdrawData::try_from(&ix.data[1..]).unwrap().amount, LAMPORTS_PER_SOL / 4);
+        assert!(send(&mut svm, from_client(ix), &user).is_ok(), "Partial withdraw should succeed");
+
+        let recipient = Pubkey::new_unique();
+        let ix = client::withdraw_to(&owner, &address(&recipient), LAMPORTS_PER_SOL / 4, &[]);
+        assert_eq!(flags(&ix), [(false, true), (true, true), (false, true), (false, false), (false, true)]);
+        assert_eq!(crate::PartialWithdrawData::try_from(&ix.data[1..]).unwrap().amount, LAMPORTS_PER_SOL / 4);
+        assert!(send(&mut svm, from_client(ix), &user).is_ok(), "Withdraw to should succeed");
+        assert_eq!(svm.get_balance(&recipient).unwrap(), LAMPORTS_PER_SOL / 4);
+
+        let ix = client::extend_lock(&owner, now, &[]);
+        assert_eq!(flags(&ix), [(true, false), (false, true)]);
+        assert_eq!(crate::ExtendLockData::try_from(&ix.data[1..]).unwrap().unlock_timestamp, now);
+        assert!(send(&mut svm, from_client(ix), &user).is_ok(), "Extend lock should succeed");
+
+        let ix = client::set_withdraw_cap(&owner, 10 * LAMPORTS_PER_SOL, &[]);
+        assert_eq!(flags(&ix), [(true, false), (false, true)]);
+        assert_eq!(crate::SetWithdrawCapData::try_from(&ix.data[1..]).unwrap().cap, 10 * LAMPORTS_PER_SOL);
+        assert!(send(&mut svm, from_client(ix), &user).is_ok(), "Set withdraw cap should succeed");
+
+        // Multisig signers trail the fixed accounts as read-only signers
+        let cosigner = address(&Pubkey::new_unique());
+        let ix = client::withdraw(&owner, &[cosigner]);
+        assert_eq!(ix.accounts[4].pubkey, cosigner);
+        assert_eq!(flags(&ix)[4], (true, false));
+
+        let ix = client::withdraw(&owner, &[]);
+        assert_eq!(flags(&ix), [(true, true), (false, true), (false, false), (false, true)]);
+        assert_eq!(ix.data, [*crate::Withdraw::DISCRIMINATOR]);
+        assert!(send(&mut svm, from_client(ix), &user).is_ok(), "Withdraw should succeed");
+
+        let account = svm.get_account(&Pubkey::new_from_array(state.to_bytes())).unwrap();
+        let decoded = client::decode_state(&account.data).unwrap();
+        assert_eq!(decoded.owner, owner);
+        assert_eq!(decoded.unlock_timestamp(), now);
+        assert_eq!(decoded.withdraw_cap(), 10 * LAMPORTS_PER_SOL);
+        assert_eq!(decoded.total_deposited(), 2 * LAMPORTS_PER_SOL);
+        assert_eq!(decoded.total_withdrawn(), 2 * LAMPORTS_PER_SOL);
+
+        // Anything but a state account is rejected
+        assert!(client::decode_state(&account.data[1..]).is_none());
+        assert!(client::decode_state(&[0; crate::VaultState::LEN]).is_none());
+    }
+
+    #[test]
+    fn test_cpi_builders_match_client() {
+        let owner = address(&Pubkey::new_unique());
+        let payer = address(&Pubkey::new_unique());
+        let treasury = address(&TREASURY);
+        let hook = address(&Pubkey::new_unique());
+        let (vault, _) = crate::find_vault_address(&owner);
+        let (state, _) = crate::find_state_address(&owner);
+        let (config, _) = crate::find_config_address();
+
+        for hook_program in [None, Some(&hook)] {
+            let (accounts, len) = crate::cpi::deposit_accounts(
+                &owner,
+                &vault,
+                &pinocchio_system::ID,
+                &state,
+                &config,
+                &treasury,
+                hook_program,
+            );
+            let data = crate::cpi::amount_data(*crate::Deposit::DISCRIMINATOR, LAMPORTS_PER_SOL);
+            let mut expected = client::deposit(&owner, &treasury, LAMPORTS_PER_SOL);
+            if let Some(hook) = hook_program {
+                expected = client::with_hook(expected, hook);
+            }
+            assert_cpi_matches(&accounts[..len], &data, &expected);
+
+            let (accounts, len) = crate::cpi::deposit_for_accounts(
+                &payer,
+                &owner,
+                &vault,
+                &pinocchio_system::ID,
+                &state,
+                &config,
+                &treasury,
+                hook_program,
+            );
+            let data = crate::cpi::amount_data(*crate::DepositFor::DISCRIMINATOR, LAMPORTS_PER_SOL);
+            let mut expected = client::deposit_for(&payer, &owner, &treasury, LAMPORTS_PER_SOL);
+            if let Some(hook) = hook_program {
+                expected = client::with_hook(expected, hook);
+            }
+            assert_cpi_matches(&accounts[..len], &data, &expected);
+        }
+    }
+
+    #[test]
+    fn test_deposit_and_withdraw() {
+        let (mut svm, user, vault_pda) = initialized();
+
+        // Deposit 1 SOL
+        let result = send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user);
+        assert!(result.is_ok(), "Deposit should succeed");
+
+        let vault_account = svm.get_account(&vault_pda).unwrap();
+        assert_eq!(vault_account.lamports, LAMPORTS_PER_SOL);
+
+        // The deposit was logged as a single event
+        let event = crate::VaultEvent {
+            kind: crate::VaultEventKind::Deposit,
+            owner: address(&user.pubkey()),
+            vault: address(&vault_pda),
+            amount: LAMPORTS_PER_SOL,
+            balance: LAMPORTS_PER_SOL,
+        };
+        assert_eq!(client::parse_events(&result.unwrap().logs), [event]);
+
+        // Withdraw everything
+        let result = send(&mut svm, create_withdraw_ix(&user.pubkey(), &vault_pda), &user);
+        assert!(result.is_ok(), "Withdraw should succeed");
+
+        let vault_account = svm.get_account(&vault_pda);
+        assert!(
+            vault_account.is_none() || vault_account.unwrap().lamports == 0,
+            "Vault should be empty after withdraw"
+        );
+
+        // So was the withdrawal, with the vault left empty
+        let event = crate::VaultEvent {
+            kind: crate::VaultEventKind::Withdraw,
+            owner: address(&user.pubkey()),
+            vault: address(&vault_pda),
+            amount: LAMPORTS_PER_SOL,
+            balance: 0,
+        };
+        assert_eq!(client::parse_events(&result.unwrap().logs), [event]);
+
+        // State recorded both sides
+        let state = svm.get_account(&get_state_pda(&user.pubkey()).0).unwrap();
+        let state = crate::VaultState::from_bytes(&state.data).unwrap();
+        assert_eq!(state.total_deposited(), LAMPORTS_PER_SOL);
+        assert_eq!(state.total_withdrawn(), LAMPORTS_PER_SOL);
+    }
+
+    #[test]
+    fn test_withdraw_fails_for_wrong_owner() {
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user).unwrap();
+
+        // Attacker signs but points at the user's vault
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        send(&mut svm, create_initialize_ix(&attacker.pubkey(), 0), &attacker).unwrap();
+
+        let result = send(&mut svm, create_withdraw_ix(&attacker.pubkey(), &vault_pda), &attacker);
+        assert_vault_error(result, VaultError::InvalidVault);
+    }
+
+    #[test]
+    fn test_deposit_fails_with_wrong_system_program() {
+        let (mut svm, user, vault_pda) = initialized();
+
+        let mut ix = create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL);
+        ix.accounts[2] = AccountMeta::new_readonly(program_id(), false);
+
+        let result = send(&mut svm, ix, &user);
+        assert_vault_error(result, VaultError::InvalidSystemProgram);
+    }
+
+    #[test]
+    fn test_withdraw_fails_without_owner_signature() {
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user).unwrap();
+
+        // Someone else pays, the owner is passed but does not sign
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let mut ix = create_withdraw_ix(&user.pubkey(), &vault_pda);
+        ix.accounts[0] = AccountMeta::new(user.pubkey(), false);
+
+        let result = send(&mut svm, ix, &payer);
+        assert_vault_error(result, VaultError::OwnerNotSigner);
+    }
+
+    #[test]
+    fn test_deposit_fails_with_zero_amount() {
+        let (mut svm, user, vault_pda) = initialized();
+
+        let result = send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, 0), &user);
+        assert_vault_error(result, VaultError::ZeroAmount);
+    }
+
+    #[test]
+    fn test_withdraw_fails_if_vault_empty() {
+        let (mut svm, user, vault_pda) = initialized();
+
+        let result = send(&mut svm, create_withdraw_ix(&user.pubkey(), &vault_pda), &user);
+        assert_vault_error(result, VaultError::VaultEmpty);
+    }
+
+    #[test]
+    fn test_deposit_fails_before_initialize() {
+        let (mut svm, user) = setup();
+        let (vault_pda, _bump) = get_vault_pda(&user.pubkey());
+
+        let result = send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user);
+        assert_vault_error(result, VaultError::StateNotInitialized);
+    }
+
+    #[test]
+    fn test_initialize_succeeds_on_prefunded_state() {
+        let (mut svm, user) = setup();
+        let state_pda = get_state_pda(&user.pubkey()).0;
+
+        // Anyone can send lamports to the state PDA before it exists
+        prefund(&mut svm, &state_pda);
+
+        assert!(send(&mut svm, create_initialize_ix(&user.pubkey(), 0), &user).is_ok(), "Initialize should succeed");
+        let state = svm.get_account(&state_pda).unwrap();
+        assert_eq!(state.owner, program_id());
+        assert_eq!(state.lamports, svm.minimum_balance_for_rent_exemption(crate::VaultState::LEN));
+        let state = crate::VaultState::from_bytes(&state.data).unwrap();
+        assert_eq!(state.owner.to_bytes(), user.pubkey().to_bytes());
+
+        // A real second initialize is still refused
+        let result = send(&mut svm, create_initialize_ix(&user.pubkey(), 1), &user);
+        assert_vault_error(result, VaultError::StateAlreadyInitialized);
+    }
+
+    #[test]
+    fn test_partial_withdraw() {
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user).unwrap();
+
+        let partial_withdraw = |amount: u64| {
+            let mut ix = create_withdraw_ix(&user.pubkey(), &vault_pda);
+            ix.data = vec![2];
+            ix.data.extend_from_slice(&amount.to_le_bytes());
+            ix
+        };
+
+        let before = svm.get_balance(&user.pubkey()).unwrap();
+        assert!(send(&mut svm, partial_withdraw(LAMPORTS_PER_SOL / 4), &user).is_ok(), "Partial withdraw should succeed");
+        assert_eq!(svm.get_balance(&vault_pda).unwrap(), LAMPORTS_PER_SOL * 3 / 4);
+        assert_eq!(svm.get_balance(&user.pubkey()).unwrap(), before + LAMPORTS_PER_SOL / 4 - 5000);
+
+        assert_vault_error(send(&mut svm, partial_withdraw(0), &user), VaultError::ZeroAmount);
+        assert_vault_error(send(&mut svm, partial_withdraw(LAMPORTS_PER_SOL), &user), VaultError::AmountExceedsBalance);
+
+        // Leaving dust behind would make the vault rent-paying
+        let dust = LAMPORTS_PER_SOL * 3 / 4 - 1000;
+        assert_vault_error(send(&mut svm, partial_withdraw(dust), &user), VaultError::BelowRentExempt);
+
+        // Taking the exact balance empties the vault
+        assert!(send(&mut svm, partial_withdraw(LAMPORTS_PER_SOL * 3 / 4), &user).is_ok());
+        let vault = svm.get_account(&vault_pda);
+        assert!(vault.is_none() || vault.unwrap().lamports == 0, "Vault should be empty");
+
+        let state = svm.get_account(&get_state_pda(&user.pubkey()).0).unwrap();
+        let state = crate::VaultState::from_bytes(&state.data).unwrap();
+        assert_eq!(state.total_withdrawn(), LAMPORTS_PER_SOL);
+    }
+
+    #[test]
+    fn test_time_lock_blocks_withdraw_until_unlock() {
+        let (mut svm, user) = setup();
+        let (vault_pda, _bump) = get_vault_pda(&user.pubkey());
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        let unlock = clock.unix_timestamp + 1000;
+        send(&mut svm, create_initialize_ix(&user.pubkey(), unlock), &user).unwrap();
+
+        // Deposits keep working while locked
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user).unwrap();
+        let result = send(&mut svm, create_withdraw_ix(&user.pubkey(), &vault_pda), &user);
+        assert_vault_error(result, VaultError::VaultLocked);
+
+        let extend_lock = |unlock_timestamp: i64| {
+            let mut data = vec![4];
+            data.extend_from_slice(&unlock_timestamp.to_le_bytes());
+            Instruction {
+                program_id: program_id(),
+                accounts: vec![
+                    AccountMeta::new_readonly(user.pubkey(), true),
+                    AccountMeta::new(get_state_pda(&user.pubkey()).0, false),
+                ],
+                data,
+            }
+        };
+
+        // The lock can only move later
+        assert_vault_error(send(&mut svm, extend_lock(unlock - 1), &user), VaultError::LockShortened);
+        assert!(send(&mut svm, extend_lock(unlock + 1000), &user).is_ok(), "Extend lock should succeed");
+
+        // Past the original unlock time but not the extended one
+        clock.unix_timestamp = unlock;
+        svm.set_sysvar(&clock);
+        svm.expire_blockhash();
+        let result = send(&mut svm, create_withdraw_ix(&user.pubkey(), &vault_pda), &user);
+        assert_vault_error(result, VaultError::VaultLocked);
+
+        clock.unix_timestamp = unlock + 1000;
+        svm.set_sysvar(&clock);
+        svm.expire_blockhash();
+        let result = send(&mut svm, create_withdraw_ix(&user.pubkey(), &vault_pda), &user);
+        assert!(result.is_ok(), "Withdraw after unlock should succeed");
+        assert_eq!(svm.get_balance(&vault_pda).unwrap_or(0), 0);
+    }
+
+    #[test]
+    fn test_token_deposit_and_withdraw() {
+        for token_program in token_program_ids() {
+            let (mut svm, user, vault_pda) = initialized();
+            let owner = user.pubkey();
+
+            let mint = create_mint(&mut svm, &token_program, 6);
+            let owner_ata = create_token_account(&mut svm, &token_program, &mint, &owner, 1_000);
+            let vault_ata = create_token_account(&mut svm, &token_program, &mint, &vault_pda, 0);
+
+            let ix = create_token_ix(5, &owner, &mint, &owner_ata, &vault_ata, &token_program, 400);
+            assert!(send(&mut svm, ix, &user).is_ok(), "Token deposit should succeed");
+            assert_eq!(token_balance(&svm, &owner_ata), 600);
+            assert_eq!(token_balance(&svm, &vault_ata), 400);
+
+            let ix = create_token_ix(6, &owner, &mint, &owner_ata, &vault_ata, &token_program, 150);
+            assert!(send(&mut svm, ix, &user).is_ok(), "Token withdraw should succeed");
+            assert_eq!(token_balance(&svm, &owner_ata), 750);
+            assert_eq!(token_balance(&svm, &vault_ata), 250);
+
+            // A token account passed as the mint
+            let ix = create_token_ix(5, &owner, &owner_ata, &owner_ata, &vault_ata, &token_program, 100);
+            assert_vault_error(send(&mut svm, ix, &user), VaultError::InvalidMint);
+
+            // A vault token account the vault PDA has no authority over
+            let other_ata = create_token_account(&mut svm, &token_program, &mint, &owner, 0);
+            let ix = create_token_ix(6, &owner, &mint, &owner_ata, &other_ata, &token_program, 100);
+            assert_vault_error(send(&mut svm, ix, &user), VaultError::InvalidTokenAccount);
+
+            // A token account of another mint
+            let other_mint = create_mint(&mut svm, &token_program, 6);
+            let foreign_ata = create_token_account(&mut svm, &token_program, &other_mint, &owner, 1_000);
+            let ix = create_token_ix(5, &owner, &mint, &foreign_ata, &vault_ata, &token_program, 100);
+            assert_vault_error(send(&mut svm, ix, &user), VaultError::InvalidTokenAccount);
+        }
+    }
+
+    #[test]
+    fn test_multisig_requires_threshold_of_distinct_signers() {
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user).unwrap();
+
+        let alice = Keypair::new();
+        let bob = Keypair::new();
+
+        // 2-of-2 over alice and bob
+        let mut data = vec![7, 2];
+        data.extend_from_slice(alice.pubkey().as_ref());
+        data.extend_from_slice(bob.pubkey().as_ref());
+        let ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(user.pubkey(), true),
+                AccountMeta::new(get_state_pda(&user.pubkey()).0, false),
+            ],
+            data,
+        };
+        assert!(send(&mut svm, ix, &user).is_ok(), "Configure multisig should succeed");
+
+        let send_with = |svm: &mut LiteSVM, mut ix: Instruction, cosigners: &[&Keypair]| {
+            ix.accounts.extend(cosigners.iter().map(|k| AccountMeta::new_readonly(k.pubkey(), true)));
+            let mut keypairs = vec![&user];
+            keypairs.extend_from_slice(cosigners);
+            let tx = Transaction::new_signed_with_payer(&[ix], Some(&user.pubkey()), &keypairs[..], svm.latest_blockhash());
+            svm.send_transaction(tx)
+        };
+
+        // The owner alone is not enough
+        let result = send_with(&mut svm, create_withdraw_ix(&user.pubkey(), &vault_pda), &[]);
+        assert_vault_error(result, VaultError::NotEnoughSigners);
+
+        // The same signer passed twice only counts once
+        let result = send_with(&mut svm, create_withdraw_ix(&user.pubkey(), &vault_pda), &[&alice, &alice]);
+        assert_vault_error(result, VaultError::NotEnoughSigners);
+
+        // Extending the lock is guarded the same way
+        let mut extend_lock = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(user.pubkey(), true),
+                AccountMeta::new(get_state_pda(&user.pubkey()).0, false),
+            ],
+            data: vec![4],
+        };
+        extend_lock.data.extend_from_slice(&1i64.to_le_bytes());
+        let result = send_with(&mut svm, extend_lock.clone(), &[&alice]);
+        assert_vault_error(result, VaultError::NotEnoughSigners);
+        assert!(send_with(&mut svm, extend_lock, &[&alice, &bob]).is_ok(), "Extend lock should succeed");
+
+        let result = send_with(&mut svm, create_withdraw_ix(&user.pubkey(), &vault_pda), &[&alice, &bob]);
+        assert!(result.is_ok(), "Multisig withdraw should succeed");
+        assert_eq!(svm.get_balance(&vault_pda).unwrap_or(0), 0);
+    }
+
+    #[test]
+    fn test_deposit_hook_failure_fails_the_deposit() {
+        let (mut svm, user, vault_pda) = initialized();
+        let owner = address(&user.pubkey());
+
+        // The memo program rejects any instruction whose accounts did not sign,
+        // which is exactly what the vault passes to its hook
+        let memo = solana_sdk::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+        let ix = client::set_hook(&owner, Some(&address(&memo)), &[]);
+        assert!(send(&mut svm, from_client(ix), &user).is_ok(), "Set hook should succeed");
+
+        let ix = client::with_hook(client::deposit(&owner, &address(&TREASURY), LAMPORTS_PER_SOL), &address(&memo));
+        let err = send(&mut svm, from_client(ix), &user).expect_err("hook should reject").err;
+        assert_eq!(err, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+        assert_eq!(svm.get_balance(&vault_pda).unwrap_or(0), 0);
+
+        // The registered hook must be passed, and it must be the registered one
+        let ix = create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL / 2);
+        assert_vault_error(send(&mut svm, ix.clone(), &user), VaultError::InvalidHook);
+        let mut wrong_hook = ix;
+        wrong_hook.accounts.push(AccountMeta::new_readonly(system_program::ID, false));
+        assert_vault_error(send(&mut svm, wrong_hook, &user), VaultError::InvalidHook);
+
+        // The vault cannot be its own hook
+        let ix = client::set_hook(&owner, Some(&crate::ID), &[]);
+        assert_vault_error(send(&mut svm, from_client(ix), &user), VaultError::InvalidHook);
+
+        // Removing the hook restores plain deposits
+        let ix = client::set_hook(&owner, None, &[]);
+        assert!(send(&mut svm, from_client(ix), &user).is_ok(), "Remove hook should succeed");
+        let ix = create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL / 4);
+        assert!(send(&mut svm, ix, &user).is_ok(), "Deposit without hook should succeed");
+        assert_eq!(svm.get_balance(&vault_pda).unwrap(), LAMPORTS_PER_SOL / 4);
+    }
+
+    #[test]
+    fn test_deposit_hook_is_called_with_vault_and_amount() {
+        let (mut svm, user, vault_pda) = initialized();
+        let owner = address(&user.pubkey());
+
+        // Memo v1 accepts any UTF-8 data and ignores its accounts, so it stands
+        // in for a small accepting hook. The memo v3 rejection in the failure
+        // test shows that no signer flags reach the hook.
+        let memo = solana_sdk::pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo");
+        let ix = client::set_hook(&owner, Some(&address(&memo)), &[]);
+        assert!(send(&mut svm, from_client(ix), &user).is_ok(), "Set hook should succeed");
+
+        // ASCII amount bytes keep the hook data valid UTF-8
+        let amount = u64::from_le_bytes(*b"AAAA\0\0\0\0");
+        let ix = client::with_hook(client::deposit(&owner, &address(&TREASURY), amount), &address(&memo));
+        let tx = Transaction::new_signed_with_payer(
+            &[from_client(ix)],
+            Some(&user.pubkey()),
+            &[&user],
+            svm.latest_blockhash(),
+        );
+        let keys = tx.message.account_keys.clone();
+        let meta = svm.send_transaction(tx).expect("deposit with an accepting hook should succeed");
+        assert_eq!(svm.get_balance(&vault_pda).unwrap(), amount);
+
+        // The hook ran once with `[b"vaultdep", amount]` and `[vault, depositor]`
+        let calls: std::vec::Vec<_> = meta.inner_instructions[0]
+            .iter()
+            .map(|inner| &inner.instruction)
+            .filter(|ix| keys[ix.program_id_index as usize] == memo)
+            .collect();
+        assert_eq!(calls.len(), 1);
+
+        let mut data = crate::DEPOSIT_HOOK_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+        assert_eq!(calls[0].data, data);
+
+        let accounts: std::vec::Vec<_> = calls[0].accounts.iter().map(|index| keys[*index as usize]).collect();
+        assert_eq!(accounts, [vault_pda, user.pubkey()]);
+    }
+
+    #[test]
+    fn test_beneficiary_claims_after_inactivity() {
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user).unwrap();
+
+        let heir = Keypair::new();
+        svm.airdrop(&heir.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let period = 30 * 24 * 60 * 60;
+        send(&mut svm, create_set_beneficiary_ix(&user.pubkey(), &heir.pubkey(), period), &user).unwrap();
+
+        // Owner is still active
+        let result = send(&mut svm, create_claim_ix(&heir.pubkey(), &user.pubkey(), &vault_pda), &heir);
+        assert_vault_error(result, VaultError::OwnerStillActive);
+
+        // Jump past the inactivity period
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += period;
+        svm.set_sysvar(&clock);
+
+        let result = send(&mut svm, create_claim_ix(&heir.pubkey(), &user.pubkey(), &vault_pda), &heir);
+        assert!(result.is_ok(), "Claim should succeed");
+        assert!(svm.get_balance(&heir.pubkey()).unwrap() > LAMPORTS_PER_SOL);
+    }
+
+    #[test]
+    fn test_third_party_deposit() {
+        let (mut svm, user, vault_pda) = initialized();
+
+        let friend = Keypair::new();
+        svm.airdrop(&friend.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+        let ix = create_deposit_for_ix(&friend.pubkey(), &user.pubkey(), &vault_pda, LAMPORTS_PER_SOL);
+        let result = send(&mut svm, ix, &friend);
+        assert!(result.is_ok(), "Third-party deposit should succeed");
+        assert_eq!(svm.get_balance(&vault_pda).unwrap(), LAMPORTS_PER_SOL);
+
+        // The payer cannot take the funds back out
+        let result = send(&mut svm, create_withdraw_ix(&friend.pubkey(), &vault_pda), &friend);
+        assert_vault_error(result, VaultError::InvalidVault);
+    }
+
+    #[test]
+    fn test_withdraw_to_recipient() {
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user).unwrap();
+
+        let merchant = Pubkey::new_unique();
+        let ix = create_withdraw_to_ix(&user.pubkey(), &vault_pda, &merchant, LAMPORTS_PER_SOL / 2);
+        let result = send(&mut svm, ix, &user);
+        assert!(result.is_ok(), "Withdraw to recipient should succeed");
+
+        assert_eq!(svm.get_balance(&merchant).unwrap(), LAMPORTS_PER_SOL / 2);
+        assert_eq!(svm.get_balance(&vault_pda).unwrap(), LAMPORTS_PER_SOL / 2);
+
+        // The vault cannot pay itself
+        let ix = create_withdraw_to_ix(&user.pubkey(), &vault_pda, &vault_pda, LAMPORTS_PER_SOL / 2);
+        assert_vault_error(send(&mut svm, ix, &user), VaultError::InvalidRecipient);
+    }
+
+    #[test]
+    fn test_relayed_withdraw_with_owner_signature() {
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user).unwrap();
+
+        // The relayer pays the fees; the owner never signs the transaction
+        let relayer = Keypair::new();
+        svm.airdrop(&relayer.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let recipient = Pubkey::new_unique();
+
+        let state = svm.get_account(&get_state_pda(&user.pubkey()).0).unwrap();
+        let init_slot = crate::VaultState::from_bytes(&state.data).unwrap().init_slot();
+        let ixs =
+            create_withdraw_signed_ixs(&user, &vault_pda, &recipient, init_slot, LAMPORTS_PER_SOL / 2, 0, i64::MAX);
+        let tx = Transaction::new_signed_with_payer(&ixs, Some(&relayer.pubkey()), &[&relayer], svm.latest_blockhash());
+        assert!(svm.send_transaction(tx).is_ok(), "Relayed withdraw should succeed");
+        assert_eq!(svm.get_balance(&recipient).unwrap(), LAMPORTS_PER_SOL / 2);
+
+        // The same authorization cannot be replayed
+        svm.expire_blockhash();
+        let tx = Transaction::new_signed_with_payer(&ixs, Some(&relayer.pubkey()), &[&relayer], svm.latest_blockhash());
+        assert_vault_error_at(svm.send_transaction(tx), 1, VaultError::InvalidNonce);
+
+        // Nor redirected to another recipient
+        let [ed25519_ix, mut withdraw_ix] =
+            create_withdraw_signed_ixs(&user, &vault_pda, &recipient, init_slot, LAMPORTS_PER_SOL / 2, 1, i64::MAX);
+        withdraw_ix.accounts[0] = AccountMeta::new(relayer.pubkey(), false);
+        let tx = Transaction::new_signed_with_payer(
+            &[ed25519_ix, withdraw_ix],
+            Some(&relayer.pubkey()),
+            &[&relayer],
+            svm.latest_blockhash(),
+        );
+        assert_vault_error_at(svm.send_transaction(tx), 1, VaultError::InvalidSignature);
+
+        // Nor carried over from another initialization of the vault
+        let ixs =
+            create_withdraw_signed_ixs(&user, &vault_pda, &recipient, init_slot + 1, LAMPORTS_PER_SOL / 2, 1, i64::MAX);
+        let tx = Transaction::new_signed_with_payer(&ixs, Some(&relayer.pubkey()), &[&relayer], svm.latest_blockhash());
+        assert_vault_error_at(svm.send_transaction(tx), 1, VaultError::InvalidSignature);
+    }
+
+    #[test]
+    fn test_allowlist_restricts_withdraw_destinations() {
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user).unwrap();
+
+        let merchant = Pubkey::new_unique();
+        let exchange = Pubkey::new_unique();
+        send(&mut svm, create_add_allowlist_entry_ix(&user.pubkey(), &merchant, 0), &user).unwrap();
+        send(&mut svm, create_add_allowlist_entry_ix(&user.pubkey(), &exchange, 3600), &user).unwrap();
+
+        // Listed and active
+        let ix = create_withdraw_to_ix(&user.pubkey(), &vault_pda, &merchant, LAMPORTS_PER_SOL / 4);
+        assert!(send(&mut svm, ix, &user).is_ok(), "Allowlisted withdraw should succeed");
+
+        // Listed but still inside its activation delay
+        let ix = create_withdraw_to_ix(&user.pubkey(), &vault_pda, &exchange, LAMPORTS_PER_SOL / 4);
+        assert_vault_error(send(&mut svm, ix, &user), VaultError::AllowlistEntryPending);
+
+        // Not listed, including the owner itself
+        let ix = create_withdraw_to_ix(&user.pubkey(), &vault_pda, &Pubkey::new_unique(), LAMPORTS_PER_SOL / 4);
+        assert_vault_error(send(&mut svm, ix, &user), VaultError::DestinationNotAllowed);
+        let result = send(&mut svm, create_withdraw_ix(&user.pubkey(), &vault_pda), &user);
+        assert_vault_error(result, VaultError::DestinationNotAllowed);
+    }
+
+    #[test]
+    fn test_withdraw_cap_per_epoch() {
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, 5 * LAMPORTS_PER_SOL), &user).unwrap();
+        send(&mut svm, create_set_withdraw_cap_ix(&user.pubkey(), LAMPORTS_PER_SOL), &user).unwrap();
+
+        let merchant = Pubkey::new_unique();
+        let withdraw = |amount| create_withdraw_to_ix(&user.pubkey(), &vault_pda, &merchant, amount);
+
+        // Amounts differ so no two transactions share a signature
+        assert!(send(&mut svm, withdraw(LAMPORTS_PER_SOL / 2), &user).is_ok());
+        assert_vault_error(send(&mut svm, withdraw(LAMPORTS_PER_SOL * 6 / 10), &user), VaultError::WithdrawLimitExceeded);
+
+        // Raising the cap does not apply straight away
+        send(&mut svm, create_set_withdraw_cap_ix(&user.pubkey(), 3 * LAMPORTS_PER_SOL), &user).unwrap();
+        assert_vault_error(send(&mut svm, withdraw(LAMPORTS_PER_SOL * 7 / 10), &user), VaultError::WithdrawLimitExceeded);
+
+        // A new epoch resets the allowance under the old cap
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.epoch += 1;
+        svm.set_sysvar(&clock);
+        assert!(send(&mut svm, withdraw(LAMPORTS_PER_SOL), &user).is_ok());
+        assert_vault_error(send(&mut svm, withdraw(LAMPORTS_PER_SOL / 10), &user), VaultError::WithdrawLimitExceeded);
+
+        // Once the delay has passed the raised cap applies
+        clock.unix_timestamp += 24 * 60 * 60;
+        svm.set_sysvar(&clock);
+        assert!(send(&mut svm, withdraw(LAMPORTS_PER_SOL * 9 / 10), &user).is_ok());
+
+        // Lowering the cap is measured against a raise that has come into
+        // force, even before any withdrawal applied it
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, 4 * LAMPORTS_PER_SOL), &user).unwrap();
+        send(&mut svm, create_set_withdraw_cap_ix(&user.pubkey(), 4 * LAMPORTS_PER_SOL), &user).unwrap();
+        clock.unix_timestamp += 24 * 60 * 60;
+        clock.epoch += 1;
+        svm.set_sysvar(&clock);
+        send(&mut svm, create_set_withdraw_cap_ix(&user.pubkey(), LAMPORTS_PER_SOL * 7 / 2), &user).unwrap();
+        assert!(send(&mut svm, withdraw(LAMPORTS_PER_SOL * 32 / 10), &user).is_ok());
+        assert_vault_error(send(&mut svm, withdraw(LAMPORTS_PER_SOL * 4 / 10), &user), VaultError::WithdrawLimitExceeded);
+    }
+
+    #[test]
+    fn test_stream_claim_and_cancel() {
+        let (mut svm, user) = setup();
+        let contractor = Keypair::new();
+        svm.airdrop(&contractor.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        let start = clock.unix_timestamp;
+
+        let mut data = vec![17];
+        data.extend_from_slice(&LAMPORTS_PER_SOL.to_le_bytes());
+        data.extend_from_slice(&start.to_le_bytes());
+        data.extend_from_slice(&(start + 1000).to_le_bytes());
+        let ix = create_stream_ix(data, &user.pubkey(), &contractor.pubkey(), true);
+        assert!(send(&mut svm, ix, &user).is_ok(), "Create stream should succeed");
+
+        // A quarter of the way through, a quarter has vested
+        clock.unix_timestamp = start + 250;
+        svm.set_sysvar(&clock);
+        let before = svm.get_balance(&contractor.pubkey()).unwrap();
+        let ix = create_stream_ix(vec![18], &user.pubkey(), &contractor.pubkey(), false);
+        assert!(send(&mut svm, ix, &contractor).is_ok(), "Claim stream should succeed");
+        let claimed = svm.get_balance(&contractor.pubkey()).unwrap() + 5000 - before;
+        assert_eq!(claimed, LAMPORTS_PER_SOL / 4);
+
+        // Cancelling halfway pays the next quarter and refunds the rest
+        clock.unix_timestamp = start + 500;
+        svm.set_sysvar(&clock);
+        let ix = create_stream_ix(vec![19], &user.pubkey(), &contractor.pubkey(), true);
+        assert!(send(&mut svm, ix, &user).is_ok(), "Cancel stream should succeed");
+        assert_eq!(
+            svm.get_balance(&contractor.pubkey()).unwrap() + 5000 - before,
+            LAMPORTS_PER_SOL / 2
+        );
+
+        let (stream, _) = Pubkey::find_program_address(
+            &[b"stream", user.pubkey().as_ref(), contractor.pubkey().as_ref()],
+            &program_id(),
+        );
+        let stream = svm.get_account(&stream);
+        assert!(stream.is_none() || stream.unwrap().lamports == 0, "Stream should be closed");
+    }
+
+    #[test]
+    fn test_stream_vesting_spans_the_whole_i64_range() {
+        let mut data = vec![0; crate::Stream::LEN];
+        let stream = crate::Stream::from_bytes_mut(&mut data).unwrap();
+        let (owner, recipient) = (address(&Pubkey::new_unique()), address(&Pubkey::new_unique()));
+        stream.init(&owner, &recipient, u64::MAX, i64::MIN, i64::MAX, 255, 255);
+
+        assert_eq!(stream.vested(i64::MIN), 0);
+        assert_eq!(stream.vested(0), 1 << 63);
+        assert_eq!(stream.vested(i64::MAX - 1), u64::MAX - 1);
+        assert_eq!(stream.vested(i64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_create_stream_succeeds_on_prefunded_accounts() {
+        let (mut svm, user) = setup();
+        let contractor = Pubkey::new_unique();
+
+        let (stream, _) =
+            Pubkey::find_program_address(&[b"stream", user.pubkey().as_ref(), contractor.as_ref()], &program_id());
+        let (escrow, _) = Pubkey::find_program_address(&[b"stream_escrow", stream.as_ref()], &program_id());
+        prefund(&mut svm, &stream);
+        prefund(&mut svm, &escrow);
+        let dust = svm.minimum_balance_for_rent_exemption(0);
+
+        let start = svm.get_sysvar::<Clock>().unix_timestamp;
+        let mut data = vec![17];
+        data.extend_from_slice(&LAMPORTS_PER_SOL.to_le_bytes());
+        data.extend_from_slice(&start.to_le_bytes());
+        data.extend_from_slice(&(start + 1000).to_le_bytes());
+        let ix = create_stream_ix(data, &user.pubkey(), &contractor, true);
+        assert!(send(&mut svm, ix.clone(), &user).is_ok(), "Create stream should succeed");
+
+        let account = svm.get_account(&stream).unwrap();
+        assert_eq!(account.owner, program_id());
+        assert_eq!(account.lamports, svm.minimum_balance_for_rent_exemption(crate::Stream::LEN));
+
+        // The escrow holds the amount and its reserve, the dust counting towards them
+        assert_eq!(svm.get_balance(&escrow).unwrap(), LAMPORTS_PER_SOL + dust);
+
+        svm.expire_blockhash();
+        assert_vault_error(send(&mut svm, ix, &user), VaultError::StreamAlreadyExists);
+    }
+
+    #[test]
+    fn test_close_returns_everything_and_requires_reinitialize() {
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user).unwrap();
+
+        // Not in the slot the vault was initialized in
+        let result = send(&mut svm, create_close_ix(&user.pubkey(), &vault_pda), &user);
+        assert_vault_error(result, VaultError::VaultNotClosable);
+        svm.warp_to_slot(1);
+        svm.expire_blockhash();
+
+        let state_pda = get_state_pda(&user.pubkey()).0;
+        let state_rent = svm.get_balance(&state_pda).unwrap();
+        let before = svm.get_balance(&user.pubkey()).unwrap();
+
+        let result = send(&mut svm, create_close_ix(&user.pubkey(), &vault_pda), &user);
+        assert!(result.is_ok(), "Close should succeed");
+
+        // Vault balance and state rent both went back to the owner, minus the fee
+        assert_eq!(svm.get_balance(&user.pubkey()).unwrap(), before + LAMPORTS_PER_SOL + state_rent - 5000);
+        let state = svm.get_account(&state_pda);
+        assert!(state.is_none() || state.unwrap().lamports == 0, "State should be closed");
+
+        // Deposits need a fresh initialize
+        let result = send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL / 2), &user);
+        assert_vault_error(result, VaultError::StateNotInitialized);
+
+        // Different unlock timestamp than the first initialize so the transaction is new
+        send(&mut svm, create_initialize_ix(&user.pubkey(), 1), &user).unwrap();
+        let result = send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL / 4), &user);
+        assert!(result.is_ok(), "Deposit after re-initialize should succeed");
+    }
+
+    #[test]
+    fn test_close_cannot_reset_vault_protections() {
+        // An empty vault still honours its time lock
+        let (mut svm, user) = setup();
+        let (vault_pda, _bump) = get_vault_pda(&user.pubkey());
+        let unlock = svm.get_sysvar::<Clock>().unix_timestamp + 1000;
+        send(&mut svm, create_initialize_ix(&user.pubkey(), unlock), &user).unwrap();
+        svm.warp_to_slot(1);
+        let result = send(&mut svm, create_close_ix(&user.pubkey(), &vault_pda), &user);
+        assert_vault_error(result, VaultError::VaultLocked);
+
+        // An allowlist or a withdrawal cap can't be dropped by starting over
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_add_allowlist_entry_ix(&user.pubkey(), &Pubkey::new_unique(), 0), &user).unwrap();
+        svm.warp_to_slot(1);
+        let result = send(&mut svm, create_close_ix(&user.pubkey(), &vault_pda), &user);
+        assert_vault_error(result, VaultError::VaultNotClosable);
+
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_set_withdraw_cap_ix(&user.pubkey(), LAMPORTS_PER_SOL), &user).unwrap();
+        svm.warp_to_slot(1);
+        let result = send(&mut svm, create_close_ix(&user.pubkey(), &vault_pda), &user);
+        assert_vault_error(result, VaultError::VaultNotClosable);
+
+        // A signed withdrawal from before the close doesn't verify after the
+        // re-initialization restarts the nonce
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user).unwrap();
+        let recipient = Pubkey::new_unique();
+        let ixs = create_withdraw_signed_ixs(&user, &vault_pda, &recipient, 0, LAMPORTS_PER_SOL / 2, 0, i64::MAX);
+        let tx = Transaction::new_signed_with_payer(&ixs, Some(&user.pubkey()), &[&user], svm.latest_blockhash());
+        assert!(svm.send_transaction(tx).is_ok(), "Relayed withdraw should succeed");
+
+        svm.warp_to_slot(1);
+        send(&mut svm, create_close_ix(&user.pubkey(), &vault_pda), &user).unwrap();
+        send(&mut svm, create_initialize_ix(&user.pubkey(), 1), &user).unwrap();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL / 2), &user).unwrap();
+
+        svm.expire_blockhash();
+        let tx = Transaction::new_signed_with_payer(&ixs, Some(&user.pubkey()), &[&user], svm.latest_blockhash());
+        assert_vault_error_at(svm.send_transaction(tx), 1, VaultError::InvalidSignature);
+        assert_eq!(svm.get_balance(&recipient).unwrap(), LAMPORTS_PER_SOL / 2);
+    }
+
+    #[test]
+    fn test_deposit_fee_goes_to_configured_treasury() {
+        let (mut svm, user, vault_pda) = initialized();
+
+        // Only the upgrade authority can create the config, even once someone
+        // has sent lamports to its address
+        let admin = Keypair::new();
+        svm.airdrop(&admin.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        prefund(&mut svm, &get_config_pda().0);
+        let ix = create_initialize_config_ix(&mut svm, &admin.pubkey(), &TREASURY, 100);
+        let mut stolen = ix.clone();
+        stolen.accounts[0] = AccountMeta::new(user.pubkey(), true);
+        assert_vault_error(send(&mut svm, stolen, &user), VaultError::InvalidAdmin);
+        assert!(send(&mut svm, ix, &admin).is_ok(), "Initialize config should succeed");
+
+        // 1% of the deposit goes to the treasury
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user).unwrap();
+        assert_eq!(svm.get_balance(&vault_pda).unwrap(), LAMPORTS_PER_SOL * 99 / 100);
+        let treasury_balance = svm.get_balance(&TREASURY).unwrap();
+        assert_eq!(treasury_balance, LAMPORTS_PER_SOL / 100);
+
+        // A treasury that doesn't match the config is rejected
+        let mut ix = create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL / 2);
+        ix.accounts[5] = AccountMeta::new(user.pubkey(), false);
+        assert_vault_error(send(&mut svm, ix, &user), VaultError::InvalidTreasury);
+
+        // Only the admin can update, and the fee stays capped
+        let mut data = vec![22];
+        data.extend_from_slice(admin.pubkey().as_ref());
+        data.extend_from_slice(TREASURY.as_ref());
+        data.extend_from_slice(&501u16.to_le_bytes());
+        let mut ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(user.pubkey(), true),
+                AccountMeta::new(get_config_pda().0, false),
+            ],
+            data,
+        };
+        assert_vault_error(send(&mut svm, ix.clone(), &user), VaultError::InvalidAdmin);
+        ix.accounts[0] = AccountMeta::new_readonly(admin.pubkey(), true);
+        assert_vault_error(send(&mut svm, ix.clone(), &admin), VaultError::FeeTooHigh);
+
+        // Dropping the fee to zero sends whole deposits to the vault
+        ix.data[65..].copy_from_slice(&0u16.to_le_bytes());
+        assert!(send(&mut svm, ix.clone(), &admin).is_ok(), "Update config should succeed");
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL / 4), &user).unwrap();
+        assert_eq!(svm.get_balance(&TREASURY).unwrap(), treasury_balance);
+
+        // Raising it again only applies after the delay
+        ix.data[65..].copy_from_slice(&200u16.to_le_bytes());
+        assert!(send(&mut svm, ix, &admin).is_ok(), "Update config should succeed");
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL / 5), &user).unwrap();
+        assert_eq!(svm.get_balance(&TREASURY).unwrap(), treasury_balance);
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += crate::FEE_CHANGE_DELAY;
+        svm.set_sysvar(&clock);
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL), &user).unwrap();
+        assert_eq!(svm.get_balance(&TREASURY).unwrap(), treasury_balance + LAMPORTS_PER_SOL * 2 / 100);
+    }
+
+    #[test]
+    fn test_config_fee_covers_the_whole_u64_range() {
+        let mut data = vec![0; crate::Config::LEN];
+        let config = crate::Config::from_bytes_mut(&mut data).unwrap();
+        let admin = address(&Pubkey::new_unique());
+        config.init(&admin, &address(&TREASURY), crate::MAX_FEE_BPS, 255).unwrap();
+
+        assert_eq!(config.fee(u64::MAX, 0).unwrap(), 922_337_203_685_477_580);
+
+        // A raise is pending until the delay has passed; a cut is immediate
+        config.set(&admin, &address(&TREASURY), 0, 0).unwrap();
+        config.set(&admin, &address(&TREASURY), 100, 0).unwrap();
+        assert_eq!(config.fee_bps(crate::FEE_CHANGE_DELAY - 1), 0);
+        assert_eq!(config.fee_bps(crate::FEE_CHANGE_DELAY), 100);
+        config.set(&admin, &address(&TREASURY), 50, crate::FEE_CHANGE_DELAY).unwrap();
+        assert_eq!(config.fee_bps(crate::FEE_CHANGE_DELAY), 50);
+        assert_eq!(config.pending_fee_bps(), None);
+    }
+
+    #[test]
+    fn test_delegate_stake_validates_vault_accounts() {
+        let (mut svm, user, vault_pda) = initialized();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, 2 * LAMPORTS_PER_SOL), &user).unwrap();
+        let vote = Pubkey::new_unique();
+
+        // The vault only signs CPIs into the real stake program
+        let mut ix = create_delegate_stake_ix(&user.pubkey(), &vote, LAMPORTS_PER_SOL);
+        ix.accounts[9] = AccountMeta::new_readonly(program_id(), false);
+        assert_vault_error(send(&mut svm, ix, &user), VaultError::InvalidStakeProgram);
+
+        // The stake account must be the owner's stake PDA
+        let mut ix = create_delegate_stake_ix(&user.pubkey(), &vote, LAMPORTS_PER_SOL);
+        ix.accounts[8] = AccountMeta::new(Pubkey::new_unique(), false);
+        assert_vault_error(send(&mut svm, ix, &user), VaultError::InvalidStakeAccount);
+
+        // Another signer cannot stake the user's vault
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let mut ix = create_delegate_stake_ix(&user.pubkey(), &vote, LAMPORTS_PER_SOL);
+        ix.accounts[5] = AccountMeta::new_readonly(attacker.pubkey(), true);
+        assert_vault_error(send(&mut svm, ix, &attacker), VaultError::InvalidVault);
+
+        // Cannot stake more than the vault holds
+        let ix = create_delegate_stake_ix(&user.pubkey(), &vote, 3 * LAMPORTS_PER_SOL);
+        assert_vault_error(send(&mut svm, ix, &user), VaultError::AmountExceedsBalance);
+        assert_eq!(svm.get_balance(&vault_pda).unwrap(), 2 * LAMPORTS_PER_SOL);
+
+        // Lamports sent to the stake PDA beforehand don't block staking: the
+        // account is created and initialized, and only the delegation to the
+        // made-up vote account fails
+        let stake_pda = get_stake_pda(&user.pubkey()).0;
+        prefund(&mut svm, &stake_pda);
+        let ix = create_delegate_stake_ix(&user.pubkey(), &vote, LAMPORTS_PER_SOL);
+        let failed = send(&mut svm, ix, &user).expect_err("delegating to a missing vote account should fail");
+        let initialized = std::format!("Program {} success", Pubkey::new_from_array(crate::STAKE_PROGRAM_ID.to_bytes()));
+        assert!(failed.meta.logs.contains(&initialized), "stake account should be initialized");
+        assert_ne!(
+            failed.err,
+            TransactionError::InstructionError(0, InstructionError::Custom(VaultError::InvalidStakeAccount as u32))
+        );
+    }
+
+    #[test]
+    fn test_stake_round_trip_returns_lamports_to_vault() {
+        let (mut svm, user) = setup();
+        let owner = address(&user.pubkey());
+        let (vault_pda, _bump) = get_vault_pda(&user.pubkey());
+        let stake_pda = get_stake_pda(&user.pubkey()).0;
+
+        let unlock = svm.get_sysvar::<Clock>().unix_timestamp + 1000;
+        send(&mut svm, create_initialize_ix(&user.pubkey(), unlock), &user).unwrap();
+        send(&mut svm, create_deposit_ix(&user.pubkey(), &vault_pda, 3 * LAMPORTS_PER_SOL), &user).unwrap();
+
+        let vote = create_vote_account(&mut svm);
+        let ix = create_delegate_stake_ix(&user.pubkey(), &vote, 2 * LAMPORTS_PER_SOL);
+        assert!(send(&mut svm, ix, &user).is_ok(), "Delegate stake should succeed");
+        assert_eq!(svm.get_balance(&vault_pda).unwrap(), LAMPORTS_PER_SOL);
+        assert_eq!(svm.get_balance(&stake_pda).unwrap(), 2 * LAMPORTS_PER_SOL);
+
+        // While the stake is out the vault stays locked, and closing it to
+        // start over with a fresh state is refused
+        svm.warp_to_slot(1);
+        let result = send(&mut svm, create_withdraw_ix(&user.pubkey(), &vault_pda), &user);
+        assert_vault_error(result, VaultError::VaultLocked);
+        let result = send(&mut svm, create_close_ix(&user.pubkey(), &vault_pda), &user);
+        assert_vault_error(result, VaultError::VaultNotClosable);
+
+        // Cool down over the next epoch, then bring everything back
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.epoch += 1;
+        svm.set_sysvar(&clock);
+        let ix = from_client(client::deactivate_stake(&owner, &[]));
+        assert!(send(&mut svm, ix, &user).is_ok(), "Deactivate stake should succeed");
+
+        clock.epoch += 1;
+        svm.set_sysvar(&clock);
+        let ix = from_client(client::withdraw_stake(&owner, &[]));
+        assert!(send(&mut svm, ix, &user).is_ok(), "Withdraw stake should succeed");
+        assert_eq!(svm.get_balance(&vault_pda).unwrap(), 3 * LAMPORTS_PER_SOL);
+        assert_eq!(svm.get_balance(&stake_pda).unwrap_or(0), 0);
+
+        // The returned lamports are still behind the original time lock
+        svm.expire_blockhash();
+        let result = send(&mut svm, create_withdraw_ix(&user.pubkey(), &vault_pda), &user);
+        assert_vault_error(result, VaultError::VaultLocked);
+
+        // Once it has passed the vault closes with the whole balance
+        clock.unix_timestamp = unlock;
+        svm.set_sysvar(&clock);
+        let before = svm.get_balance(&user.pubkey()).unwrap();
+        let result = send(&mut svm, create_close_ix(&user.pubkey(), &vault_pda), &user);
+        assert!(result.is_ok(), "Close should succeed");
+        assert!(svm.get_balance(&user.pubkey()).unwrap() > before + 3 * LAMPORTS_PER_SOL - 5000);
+    }
+
+    #[test]
+    fn test_versioned_deposit_envelope() {
+        let (mut svm, user, vault_pda) = initialized();
+
+        // Explicit version 0 is the legacy `[0, amount]` payload
+        let mut ix = create_deposit_ix(&user.pubkey(), &vault_pda, LAMPORTS_PER_SOL);
+        assert!(send(&mut svm, ix.clone(), &user).is_ok(), "Legacy deposit should succeed");
+        ix.data = vec![0x80, 0];
+        ix.data.extend_from_slice(&LAMPORTS_PER_SOL.to_le_bytes());
+        assert!(send(&mut svm, ix.clone(), &user).is_ok(), "Version 0 deposit should succeed");
+
+        // Version 1 takes an optional max fee
+        ix.data[1] = 1;
+        ix.data.extend_from_slice(&0u64.to_le_bytes());
+        assert!(send(&mut svm, ix.clone(), &user).is_ok(), "Version 1 deposit should succeed");
+        assert_eq!(svm.get_balance(&vault_pda).unwrap(), 3 * LAMPORTS_PER_SOL);
+
+        // Unknown versions are rejected rather than misparsed
+        ix.data[1] = 2;
+        assert_vault_error(send(&mut svm, ix.clone(), &user), VaultError::UnsupportedVersion);
+
+        // With a 1% fee configured, a zero max fee refuses the deposit
+        let admin = Keypair::new();
+        svm.airdrop(&admin.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let config_ix = create_initialize_config_ix(&mut svm, &admin.pubkey(), &TREASURY, 100);
+        send(&mut svm, config_ix, &admin).unwrap();
+        ix.data[1] = 1;
+        svm.expire_blockhash();
+        assert_vault_error(send(&mut svm, ix, &user), VaultError::FeeTooHigh);
+
+        // Legacy encodings still work
+        let result = send(&mut svm, create_withdraw_ix(&user.pubkey(), &vault_pda), &user);
+        assert!(result.is_ok(), "Legacy withdraw should succeed");
+    }
+
+    #[test]
+    fn test_versioned_deposit_for_envelope() {
+        let (mut svm, user, vault_pda) = initialized();
+        let friend = Keypair::new();
+        svm.airdrop(&friend.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+        let payer = address(&friend.pubkey());
+        let owner = address(&user.pubkey());
+        let treasury = address(&TREASURY);
+
+        // Legacy `[10, amount]` and explicit version 0 `[0x8a, 0, amount]`
+        let mut ix = create_deposit_for_ix(&friend.pubkey(), &user.pubkey(), &vault_pda, LAMPORTS_PER_SOL);
+        assert!(send(&mut svm, ix.clone(), &friend).is_ok(), "Legacy deposit for should succeed");
+        ix.data = vec![0x8a, 0];
+        ix.data.extend_from_slice(&LAMPORTS_PER_SOL.to_le_bytes());
+        assert!(send(&mut svm, ix.clone(), &friend).is_ok(), "Version 0 deposit for should succeed");
+
+        // Version 1 takes a max fee
+        let v1 = client::deposit_for_with_max_fee(&payer, &owner, &treasury, LAMPORTS_PER_SOL, 0);
+        assert!(send(&mut svm, from_client(v1), &friend).is_ok(), "Version 1 deposit for should succeed");
+        assert_eq!(svm.get_balance(&vault_pda).unwrap(), 3 * LAMPORTS_PER_SOL);
+
+        // Unknown versions are rejected rather than misparsed
+        ix.data[1] = 2;
+        assert_vault_error(send(&mut svm, ix, &friend), VaultError::UnsupportedVersion);
+
+        // With a 1% fee configured, the max fee bounds what the payer accepts
+        let admin = Keypair::new();
+        svm.airdrop(&admin.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let config_ix = create_initialize_config_ix(&mut svm, &admin.pubkey(), &TREASURY, 100);
+        send(&mut svm, config_ix, &admin).unwrap();
+
+        let ix = client::deposit_for_with_max_fee(&payer, &owner, &treasury, LAMPORTS_PER_SOL, LAMPORTS_PER_SOL / 100 - 1);
+        assert_vault_error(send(&mut svm, from_client(ix), &friend), VaultError::FeeTooHigh);
+        let ix = client::deposit_for_with_max_fee(&payer, &owner, &treasury, LAMPORTS_PER_SOL, LAMPORTS_PER_SOL / 100);
+        assert!(send(&mut svm, from_client(ix), &friend).is_ok(), "Deposit for within the max fee should succeed");
+        assert_eq!(svm.get_balance(&TREASURY).unwrap(), LAMPORTS_PER_SOL / 100);
+    }
+}
@@ -0,0 +1,138 @@
+use pinocchio::{
+    account::AccountView,
+    address::Address,
+    cpi::Signer,
+    error::ProgramError,
+    ProgramResult,
+};
+
+use pinocchio_token::state::{Mint, TokenAccount};
+
+use crate::VaultError;
+
+const TOKEN_2022_ACCOUNT_DISCRIMINATOR_OFFSET: usize = 165;
+pub const TOKEN_2022_MINT_DISCRIMINATOR: u8 = 0x01;
+pub const TOKEN_2022_TOKEN_ACCOUNT_DISCRIMINATOR: u8 = 0x02;
+
+const MINT_DECIMALS_OFFSET: usize = 44;
+const MINT_IS_INITIALIZED_OFFSET: usize = 45;
+const TOKEN_ACCOUNT_MINT_OFFSET: usize = 0;
+const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+
+pub struct TokenProgram;
+
+impl TokenProgram {
+    /// Accepts both the Token and the Token-2022 program
+    pub fn check(account: &AccountView) -> Result<(), ProgramError> {
+        if account.address() != &pinocchio_token::ID
+            && account.address() != &pinocchio_token_2022::ID
+        {
+            return Err(VaultError::InvalidTokenProgram.into());
+        }
+        Ok(())
+    }
+}
+
+pub struct MintInterface;
+
+impl MintInterface {
+    /// Validates `account` as a mint of `token_program` and returns its decimals
+    pub fn check(account: &AccountView, token_program: &Address) -> Result<u8, ProgramError> {
+        if !account.owned_by(token_program) {
+            return Err(VaultError::InvalidMint.into());
+        }
+
+        let data = account.try_borrow()?;
+
+        if data.len() != Mint::LEN {
+            // Token-2022 mints with extensions carry an account type byte
+            if token_program != &pinocchio_token_2022::ID
+                || data.len() <= TOKEN_2022_ACCOUNT_DISCRIMINATOR_OFFSET
+                || data[TOKEN_2022_ACCOUNT_DISCRIMINATOR_OFFSET] != TOKEN_2022_MINT_DISCRIMINATOR
+            {
+                return Err(VaultError::InvalidMint.into());
+            }
+        }
+
+        if data[MINT_IS_INITIALIZED_OFFSET] != 1 {
+            return Err(VaultError::InvalidMint.into());
+        }
+
+        Ok(data[MINT_DECIMALS_OFFSET])
+    }
+}
+
+pub struct TokenAccountInterface;
+
+impl TokenAccountInterface {
+    /// Validates `account` as a `token_program` account for `mint` owned by `authority`
+    pub fn check(
+        account: &AccountView,
+        token_program: &Address,
+        mint: &Address,
+        authority: &Address,
+    ) -> Result<(), ProgramError> {
+        if !account.owned_by(token_program) {
+            return Err(VaultError::InvalidTokenAccount.into());
+        }
+
+        let data = account.try_borrow()?;
+
+        if data.len() != TokenAccount::LEN {
+            // Token-2022 accounts with extensions carry an account type byte
+            if token_program != &pinocchio_token_2022::ID
+                || data.len() <= TOKEN_2022_ACCOUNT_DISCRIMINATOR_OFFSET
+                || data[TOKEN_2022_ACCOUNT_DISCRIMINATOR_OFFSET]
+                    != TOKEN_2022_TOKEN_ACCOUNT_DISCRIMINATOR
+            {
+                return Err(VaultError::InvalidTokenAccount.into());
+            }
+        }
+
+        if &data[TOKEN_ACCOUNT_MINT_OFFSET..TOKEN_ACCOUNT_MINT_OFFSET + 32] != mint.as_ref() {
+            return Err(VaultError::InvalidTokenAccount.into());
+        }
+
+        if &data[TOKEN_ACCOUNT_OWNER_OFFSET..TOKEN_ACCOUNT_OWNER_OFFSET + 32] != authority.as_ref() {
+            return Err(VaultError::InvalidTokenAccount.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// `TransferChecked` against whichever token program owns the accounts
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked(
+    token_program: &AccountView,
+    from: &AccountView,
+    mint: &AccountView,
+    to: &AccountView,
+    authority: &AccountView,
+    amount: u64,
+    decimals: u8,
+    signers: &[Signer],
+) -> ProgramResult {
+    if token_program.address() == &pinocchio_token::ID {
+        pinocchio_token::instructions::TransferChecked {
+            from,
+            mint,
+            to,
+            authority,
+            amount,
+            decimals,
+        }
+        .invoke_signed(signers)
+    } else {
+        pinocchio_token_2022::instructions::TransferChecked {
+            from,
+            mint,
+            to,
+            authority,
+            amount,
+            decimals,
+            token_program: token_program.address(),
+        }
+        .invoke_signed(signers)
+    }
+}